@@ -0,0 +1,501 @@
+use std::time::Duration;
+
+use futures::{pin_mut, Stream, StreamExt};
+use reqwest::header::HeaderValue;
+
+use crate::{
+    admin_user_attributes::AdminUserAttributes,
+    api::{env_var, parse_json, url_from_env, Api},
+    ban_duration::BanDuration,
+    error::{ApiError, Error},
+    generate_link::{GenerateLinkParams, GenerateLinkResponse, GenerateLinkType},
+    identity::{Identity, UserIdentities},
+    list_users_params::ListUsersParams,
+    paginated_users::PaginatedUsers,
+    sso::{CreateSsoProviderRequest, SsoProvider, SsoProviderAttributes, SsoProviderList},
+    user::User,
+    user_session::UserSession,
+};
+
+/// Environment variable holding the service role key.
+const SERVICE_ROLE_KEY_VAR: &str = "GOTRUE_SERVICE_ROLE_KEY";
+
+/// Client for the admin endpoints of GoTrue.
+///
+/// Every request is authorized with the service role key, so this must only be used on a
+/// trusted server and never be handed a user's access token.
+///
+/// Like [`Api`], cloning is cheap as clones share the connection pool.
+///
+/// # Example
+///
+/// ```
+/// use go_true::AdminApi;
+///
+/// let admin = AdminApi::new("http://your.gotrue.endpoint".to_string(), "service.role.key");
+/// ```
+#[derive(Debug, Clone)]
+pub struct AdminApi {
+    api: Api,
+}
+
+impl AdminApi {
+    /// Creates an admin client sending `service_role_key` as `apikey` and bearer token.
+    pub fn new(url: String, service_role_key: &str) -> AdminApi {
+        AdminApi::new_with_api(Api::new(url), service_role_key)
+    }
+
+    /// Creates an admin client configured by environment variables.
+    ///
+    /// - `GOTRUE_URL` (required): url of the GoTrue server.
+    /// - `GOTRUE_SERVICE_ROLE_KEY` (required): sent as `apikey` header and bearer token.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfig`] if a variable is missing or invalid, see
+    /// [`Api::from_env`].
+    pub fn from_env() -> Result<AdminApi, Error> {
+        let service_role_key = env_var(SERVICE_ROLE_KEY_VAR)
+            .ok_or_else(|| Error::InvalidConfig(format!("{SERVICE_ROLE_KEY_VAR} is not set")))?;
+        if HeaderValue::from_str(&service_role_key).is_err() {
+            return Err(Error::InvalidConfig(format!(
+                "{SERVICE_ROLE_KEY_VAR} is not a valid header value"
+            )));
+        }
+
+        let api = Api::new(url_from_env()?);
+
+        Ok(AdminApi::new_with_api(api, &service_role_key))
+    }
+
+    /// Creates an admin client from a configured [`Api`], e.g. one with timeouts or retries.
+    pub fn new_with_api(api: Api, service_role_key: &str) -> AdminApi {
+        let api = api
+            .insert_header("apikey", service_role_key)
+            .insert_header("Authorization", format!("Bearer {service_role_key}"));
+
+        AdminApi { api }
+    }
+
+    /// Invites a user via email, see [`Api::invite_user_by_email`].
+    pub async fn invite_user_by_email(&self, email: &str) -> Result<User, ApiError> {
+        self.api.invite_user_by_email(email).await
+    }
+
+    /// Invites a user via email with an idempotency key, see
+    /// [`Api::invite_user_by_email_with_idempotency_key`].
+    pub async fn invite_user_by_email_with_idempotency_key(
+        &self,
+        email: &str,
+        idempotency_key: &str,
+    ) -> Result<User, ApiError> {
+        self.api
+            .invite_user_by_email_with_idempotency_key(email, idempotency_key)
+            .await
+    }
+
+    /// Invites a user via email with user metadata and a redirect target, see
+    /// [`Api::invite_user_by_email_with`].
+    pub async fn invite_user_by_email_with(
+        &self,
+        email: &str,
+        data: Option<serde_json::Value>,
+        redirect_to: Option<&str>,
+    ) -> Result<User, ApiError> {
+        self.api
+            .invite_user_by_email_with(email, data, redirect_to)
+            .await
+    }
+
+    /// Generates an email action link without sending an email, see [`Api::generate_link`].
+    pub async fn generate_link(
+        &self,
+        params: GenerateLinkParams,
+    ) -> Result<GenerateLinkResponse, ApiError> {
+        self.api.generate_link(params).await
+    }
+
+    /// Generates a password recovery link without sending an email and returns its
+    /// `action_link`, e.g. to send it with your own mail templates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::AdminApi;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let admin = AdminApi::new("http://localhost:9998".to_string(), "service.role.key");
+    ///
+    ///     let result = admin
+    ///         .generate_recovery_link("email@example.com", Some("https://example.com/reset"))
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn generate_recovery_link(
+        &self,
+        email: &str,
+        redirect_to: Option<&str>,
+    ) -> Result<String, ApiError> {
+        let mut params = GenerateLinkParams::new(GenerateLinkType::Recovery, email);
+        params.redirect_to = redirect_to.map(str::to_string);
+
+        let response = self.api.generate_link(params).await?;
+
+        Ok(response.action_link)
+    }
+
+    /// Lists users page by page, see [`Api::list_users_with`].
+    pub async fn list_users(&self, params: ListUsersParams) -> Result<PaginatedUsers, ApiError> {
+        self.api.list_users_with(params).await
+    }
+
+    /// Streams all users, see [`Api::list_users_stream`].
+    pub fn list_users_stream(
+        &self,
+        params: ListUsersParams,
+    ) -> impl Stream<Item = Result<User, ApiError>> + '_ {
+        self.api.list_users_stream(params)
+    }
+
+    /// Gets a user by id, see [`Api::get_user_by_id`].
+    pub async fn get_user_by_id(&self, user_id: &str) -> Result<User, ApiError> {
+        self.api.get_user_by_id(user_id).await
+    }
+
+    /// Gets the identities linked to a user.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::AdminApi;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let admin = AdminApi::new("http://localhost:9998".to_string(), "service.role.key");
+    ///
+    ///     let result = admin.get_user_identities("user_id").await;
+    /// }
+    /// ```
+    pub async fn get_user_identities(&self, user_id: &str) -> Result<Vec<Identity>, ApiError> {
+        let user: UserIdentities = self.api.get_user_by_id_as(user_id).await?;
+
+        Ok(user.identities.unwrap_or_default())
+    }
+
+    /// Finds the user with the given email, compared case-insensitively.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::AdminApi;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let admin = AdminApi::new("http://localhost:9998".to_string(), "service.role.key");
+    ///
+    ///     let result = admin.get_user_by_email("email@example.com").await;
+    /// }
+    /// ```
+    pub async fn get_user_by_email(&self, email: &str) -> Result<Option<User>, ApiError> {
+        let params = ListUsersParams::new().filter(email);
+
+        self.find_user(params, |user| user.email.eq_ignore_ascii_case(email))
+            .await
+    }
+
+    /// Finds the user with the given phone number, with or without a leading `+`.
+    ///
+    /// GoTrue's filter doesn't search phone numbers, so this pages through all users and should
+    /// be avoided in hot paths on large projects.
+    pub async fn get_user_by_phone(&self, phone: &str) -> Result<Option<User>, ApiError> {
+        let phone = phone.trim_start_matches('+');
+
+        self.find_user(ListUsersParams::new(), |user| {
+            !phone.is_empty() && user.phone.trim_start_matches('+') == phone
+        })
+        .await
+    }
+
+    async fn find_user(
+        &self,
+        params: ListUsersParams,
+        matches: impl Fn(&User) -> bool,
+    ) -> Result<Option<User>, ApiError> {
+        let users = self.api.list_users_stream(params);
+        pin_mut!(users);
+
+        while let Some(user) = users.next().await {
+            let user = user?;
+            if matches(&user) {
+                return Ok(Some(user));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Creates a user, see [`Api::create_user`].
+    pub async fn create_user<T: serde::Serialize>(&self, user: T) -> Result<User, ApiError> {
+        self.api.create_user(user).await
+    }
+
+    /// Creates a user with an idempotency key, see [`Api::create_user_with_idempotency_key`].
+    pub async fn create_user_with_idempotency_key<T: serde::Serialize>(
+        &self,
+        user: T,
+        idempotency_key: &str,
+    ) -> Result<User, ApiError> {
+        self.api
+            .create_user_with_idempotency_key(user, idempotency_key)
+            .await
+    }
+
+    /// Updates a user by id, see [`Api::update_user_by_id`].
+    pub async fn update_user_by_id<T: serde::Serialize>(
+        &self,
+        id: &str,
+        user: T,
+    ) -> Result<User, ApiError> {
+        self.api.update_user_by_id(id, user).await
+    }
+
+    /// Bans a user for `duration`. The user can't sign in or refresh a session until it expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::AdminApi;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let admin = AdminApi::new("http://localhost:9998".to_string(), "service.role.key");
+    ///
+    ///     let result = admin.ban_user("user_id", Duration::from_secs(24 * 60 * 60)).await;
+    /// }
+    /// ```
+    pub async fn ban_user(&self, user_id: &str, duration: Duration) -> Result<User, ApiError> {
+        self.set_ban_duration(user_id, BanDuration::For(duration))
+            .await
+    }
+
+    /// Lifts the ban of a user.
+    pub async fn unban_user(&self, user_id: &str) -> Result<User, ApiError> {
+        self.set_ban_duration(user_id, BanDuration::None).await
+    }
+
+    /// Marks the email of a user as confirmed, e.g. for a user who can't receive the
+    /// confirmation email.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::AdminApi;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let admin = AdminApi::new("http://localhost:9998".to_string(), "service.role.key");
+    ///
+    ///     let result = admin.confirm_email("user_id").await;
+    /// }
+    /// ```
+    pub async fn confirm_email(&self, user_id: &str) -> Result<User, ApiError> {
+        let attributes = AdminUserAttributes {
+            email_confirm: Some(true),
+            ..Default::default()
+        };
+
+        self.api.update_user_by_id(user_id, attributes).await
+    }
+
+    /// Marks the phone number of a user as confirmed, see [`AdminApi::confirm_email`].
+    pub async fn confirm_phone(&self, user_id: &str) -> Result<User, ApiError> {
+        let attributes = AdminUserAttributes {
+            phone_confirm: Some(true),
+            ..Default::default()
+        };
+
+        self.api.update_user_by_id(user_id, attributes).await
+    }
+
+    async fn set_ban_duration(
+        &self,
+        user_id: &str,
+        ban_duration: BanDuration,
+    ) -> Result<User, ApiError> {
+        let attributes = AdminUserAttributes {
+            ban_duration: Some(ban_duration),
+            ..Default::default()
+        };
+
+        self.api.update_user_by_id(user_id, attributes).await
+    }
+
+    /// Deletes a user, see [`Api::delete_user`].
+    pub async fn delete_user(&self, user_id: &str) -> Result<(), ApiError> {
+        self.api.delete_user(user_id).await
+    }
+
+    /// Deletes a user, optionally keeping the row in the database, see [`Api::delete_user_with`].
+    pub async fn delete_user_with(
+        &self,
+        user_id: &str,
+        should_soft_delete: bool,
+    ) -> Result<(), ApiError> {
+        self.api.delete_user_with(user_id, should_soft_delete).await
+    }
+
+    /// Lists the active sessions of a user, e.g. to show the devices they are signed in on.
+    ///
+    /// Requires a GoTrue version serving `/admin/users/{id}/sessions`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::AdminApi;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let admin = AdminApi::new("http://localhost:9998".to_string(), "service.role.key");
+    ///
+    ///     let result = admin.list_user_sessions("user_id").await;
+    /// }
+    /// ```
+    pub async fn list_user_sessions(&self, user_id: &str) -> Result<Vec<UserSession>, ApiError> {
+        let endpoint = format!("{}/admin/users/{}/sessions", self.api.url, user_id);
+
+        let request = self
+            .api
+            .client
+            .get(endpoint)
+            .headers(self.api.headers.clone());
+        let response = self.api.send_idempotent(request).await?;
+
+        parse_json(response).await
+    }
+
+    /// Revokes a session of a user. Its refresh token can't be used anymore, already issued
+    /// access tokens stay valid until they expire.
+    pub async fn delete_user_session(
+        &self,
+        user_id: &str,
+        session_id: &str,
+    ) -> Result<(), ApiError> {
+        let endpoint = format!(
+            "{}/admin/users/{}/sessions/{}",
+            self.api.url, user_id, session_id
+        );
+
+        let request = self
+            .api
+            .client
+            .delete(endpoint)
+            .headers(self.api.headers.clone());
+        self.api.send(request).await?;
+
+        Ok(())
+    }
+
+    /// Registers a SAML identity provider
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{AdminApi, SsoProviderAttributes};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let admin = AdminApi::new("http://localhost:9998".to_string(), "service.role.key");
+    ///
+    ///     let attributes = SsoProviderAttributes {
+    ///         metadata_url: Some("https://idp.example.com/saml/metadata".to_string()),
+    ///         domains: Some(vec!["example.com".to_string()]),
+    ///         ..Default::default()
+    ///     };
+    ///     let result = admin.create_sso_provider(attributes).await;
+    /// }
+    /// ```
+    pub async fn create_sso_provider(
+        &self,
+        attributes: SsoProviderAttributes,
+    ) -> Result<SsoProvider, ApiError> {
+        let endpoint = format!("{}/admin/sso/providers", self.api.url);
+        let body = CreateSsoProviderRequest {
+            provider_type: "saml",
+            attributes: &attributes,
+        };
+
+        let request = self
+            .api
+            .client
+            .post(endpoint)
+            .headers(self.api.headers.clone())
+            .json(&body);
+        let response = self.api.send(request).await?;
+
+        parse_json(response).await
+    }
+
+    /// Lists all registered SSO providers.
+    pub async fn list_sso_providers(&self) -> Result<Vec<SsoProvider>, ApiError> {
+        let endpoint = format!("{}/admin/sso/providers", self.api.url);
+
+        let request = self
+            .api
+            .client
+            .get(endpoint)
+            .headers(self.api.headers.clone());
+        let response = self.api.send_idempotent(request).await?;
+        let list: SsoProviderList = parse_json(response).await?;
+
+        Ok(list.items)
+    }
+
+    /// Gets an SSO provider by id.
+    pub async fn get_sso_provider(&self, provider_id: &str) -> Result<SsoProvider, ApiError> {
+        let endpoint = format!("{}/admin/sso/providers/{}", self.api.url, provider_id);
+
+        let request = self
+            .api
+            .client
+            .get(endpoint)
+            .headers(self.api.headers.clone());
+        let response = self.api.send_idempotent(request).await?;
+
+        parse_json(response).await
+    }
+
+    /// Updates an SSO provider, only the attributes set are changed.
+    pub async fn update_sso_provider(
+        &self,
+        provider_id: &str,
+        attributes: SsoProviderAttributes,
+    ) -> Result<SsoProvider, ApiError> {
+        let endpoint = format!("{}/admin/sso/providers/{}", self.api.url, provider_id);
+
+        let request = self
+            .api
+            .client
+            .put(endpoint)
+            .headers(self.api.headers.clone())
+            .json(&attributes);
+        let response = self.api.send(request).await?;
+
+        parse_json(response).await
+    }
+
+    /// Removes an SSO provider and returns it. Users who signed in with it are kept.
+    pub async fn delete_sso_provider(&self, provider_id: &str) -> Result<SsoProvider, ApiError> {
+        let endpoint = format!("{}/admin/sso/providers/{}", self.api.url, provider_id);
+
+        let request = self
+            .api
+            .client
+            .delete(endpoint)
+            .headers(self.api.headers.clone());
+        let response = self.api.send(request).await?;
+
+        parse_json(response).await
+    }
+}
@@ -0,0 +1,36 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::ban_duration::BanDuration;
+
+/// Attributes of a user created or updated by an admin, see
+/// [`Api::create_user`](crate::Api::create_user) and
+/// [`Api::update_user_by_id`](crate::Api::update_user_by_id).
+///
+/// Only the fields that are set are sent.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AdminUserAttributes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// User metadata, editable by the user.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_metadata: Option<Value>,
+    /// App metadata, only editable with the service role key. Use it for roles and claims that
+    /// must be trusted, it is included in the `app_metadata` claim of the user's access tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_metadata: Option<Value>,
+    /// Confirms the email right away, so the user can sign in without a confirmation email.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_confirm: Option<bool>,
+    /// Confirms the phone number right away, so the user can sign in without an SMS code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone_confirm: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ban_duration: Option<BanDuration>,
+}
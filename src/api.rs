@@ -1,72 +1,1800 @@
-use reqwest::header::{HeaderMap, HeaderValue, IntoHeaderName};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use std::{fmt, time::Duration};
+
+use futures::{stream, Stream, StreamExt};
+use reqwest::{
+    header::{
+        HeaderMap, HeaderValue, IntoHeaderName, InvalidHeaderValue, ACCEPT, AUTHORIZATION,
+        CONTENT_TYPE, RETRY_AFTER, USER_AGENT,
+    },
+    Method, Request, RequestBuilder, Response, StatusCode,
+};
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json::json;
+use url::form_urlencoded;
 
+#[cfg(feature = "verify")]
+use crate::claims::Claims;
 use crate::{
-    session::Session, user::User, user_attributes::UserAttributes, user_list::UserList,
+    api_builder::ApiBuilder,
+    email_change::EmailChangeResult,
+    error::{ApiError, Error, GoTrueErrorBody},
+    generate_link::{GenerateLinkParams, GenerateLinkResponse},
+    grant_type::GrantType,
+    health::HealthStatus,
+    id_token_params::IdTokenParams,
+    identity::{AuthorizeUrl, Identity, UserIdentities},
+    list_users_params::ListUsersParams,
+    mfa::{Factor, MfaChallengeResponse, MfaEnrollParams, MfaEnrollResponse, UserFactors},
+    oauth_options::{OAuthOptions, OAuthResponse},
+    otp_params::OtpParams,
+    paginated_users::PaginatedUsers,
+    pkce::PkceChallenge,
+    provider::Provider,
+    session::Session,
+    settings::Settings,
+    sign_out_scope::SignOutScope,
+    sign_up_options::SignUpOptions,
+    sign_up_result::SignUpResult,
+    sso::{SsoParams, SsoRequest},
+    user::{User, UserMetadata},
+    user_attributes::UserAttributes,
+    user_list::UserList,
     user_update::UserUpdate,
+    verify_otp_params::{OtpType, VerifyOtpParams},
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    response_hook::ResponseHook,
+    retry::{self, RetryConfig},
 };
 
+/// `User-Agent` sent by clients the crate builds itself.
+pub(crate) const DEFAULT_USER_AGENT: &str = concat!("go-true-rs/", env!("CARGO_PKG_VERSION"));
+
+/// Environment variable holding the url of the GoTrue server.
+const URL_VAR: &str = "GOTRUE_URL";
+
+/// Environment variable holding the key sent as `apikey` header.
+const API_KEY_VAR: &str = "GOTRUE_API_KEY";
+
+/// Header carrying the idempotency key of mutating requests.
+const IDEMPOTENCY_KEY: &str = "Idempotency-Key";
+
+/// Client for the auth endpoints of GoTrue.
+///
+/// Cloning is cheap, clones share the connection pool of the underlying [`reqwest::Client`].
+/// Store an `Api` in the state of a web application and clone it per request instead of
+/// wrapping it in an `Arc`.
+#[derive(Clone)]
 pub struct Api {
-    url: String,
-    headers: HeaderMap,
-    client: reqwest::Client,
+    pub(crate) url: String,
+    pub(crate) headers: HeaderMap,
+    pub(crate) client: reqwest::Client,
+    pub(crate) redirect_to: Option<String>,
+    pub(crate) dry_run: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) retry: Option<RetryConfig>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) on_response: Option<ResponseHook>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmailOrPhone {
+    Email(String),
+    Phone(String),
+}
+
+/// Kind of message sent again by [`Api::resend`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResendType {
+    /// Signup confirmation email.
+    Signup,
+    /// Confirmation email for an email change.
+    EmailChange,
+    /// OTP sent by SMS.
+    Sms,
+    /// OTP sent by SMS for a phone number change.
+    PhoneChange,
+}
+
+impl fmt::Debug for Api {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut debug = f.debug_struct("Api");
+        debug
+            .field("url", &self.url)
+            .field("headers", &RedactedHeaders(&self.headers))
+            .field("redirect_to", &self.redirect_to)
+            .field("dry_run", &self.dry_run);
+        #[cfg(not(target_arch = "wasm32"))]
+        debug
+            .field("retry", &self.retry)
+            .field("on_response", &self.on_response);
+        debug.finish_non_exhaustive()
+    }
+}
+
+/// Formats only the names of headers, values like `apikey` or `Authorization` hold secrets.
+pub(crate) struct RedactedHeaders<'a>(pub(crate) &'a HeaderMap);
+
+impl fmt::Debug for RedactedHeaders<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(self.0.keys().map(|name| (name, "<redacted>")))
+            .finish()
+    }
 }
 
-pub enum EmailOrPhone {
-    Email(String),
-    Phone(String),
-}
+impl Api {
+    /// Creates a GoTrue API client. Trailing slashes of `url` are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `url` is not a valid URL.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// let client = Api::new("http://your.gotrue.endpoint".to_string());
+    /// ```
+    pub fn new(url: String) -> Api {
+        let client = client_builder()
+            .build()
+            .expect("Failed to build HTTP client.");
+
+        Api::new_with_client(url, client)
+    }
+
+    /// Creates a GoTrue API client configured by environment variables.
+    ///
+    /// - `GOTRUE_URL` (required): url of the GoTrue server.
+    /// - `GOTRUE_API_KEY` (optional): sent as `apikey` header, e.g. the anon key of a Supabase
+    ///   project.
+    ///
+    /// Empty variables are treated as unset. Use [`AdminApi::from_env`](crate::AdminApi::from_env)
+    /// for the service role key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfig`] if `GOTRUE_URL` is missing or not a valid url, or if
+    /// `GOTRUE_API_KEY` isn't a valid header value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// std::env::set_var("GOTRUE_URL", "http://your.gotrue.endpoint");
+    ///
+    /// let client = Api::from_env().expect("GOTRUE_URL is set");
+    /// ```
+    pub fn from_env() -> Result<Api, Error> {
+        let api = Api::new(url_from_env()?);
+        match env_var(API_KEY_VAR) {
+            Some(api_key) => api.try_insert_header("apikey", api_key).map_err(|_| {
+                Error::InvalidConfig(format!("{API_KEY_VAR} is not a valid header value"))
+            }),
+            None => Ok(api),
+        }
+    }
+
+    /// Returns a builder to configure the url, headers, timeouts and retries in one place.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// let client = Api::builder()
+    ///     .url("http://your.gotrue.endpoint")
+    ///     .header("apikey", "super.secret.key")
+    ///     .build();
+    /// ```
+    pub fn builder() -> ApiBuilder {
+        ApiBuilder::new()
+    }
+
+    /// Creates a GoTrue API client sending requests with `client`.
+    ///
+    /// The client is used as is, so proxies, TLS, timeouts and the `User-Agent` have to be
+    /// configured on it. Use [`Api::builder`] to have them configured for you.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `url` is not a valid URL.
+    pub fn new_with_client(url: String, client: reqwest::Client) -> Api {
+        Api {
+            url: normalize_url(url),
+            headers: HeaderMap::new(),
+            client,
+            redirect_to: None,
+            dry_run: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            retry: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            on_response: None,
+        }
+    }
+
+    /// Creates a GoTrue API client whose requests fail after `timeout`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    /// use std::time::Duration;
+    ///
+    /// let client = Api::with_timeout(
+    ///     "http://your.gotrue.endpoint".to_string(),
+    ///     Duration::from_secs(10),
+    /// );
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_timeout(url: String, timeout: Duration) -> Api {
+        let client = client_builder()
+            .timeout(timeout)
+            .build()
+            .expect("Failed to build HTTP client.");
+
+        Api::new_with_client(url, client)
+    }
+
+    /// Creates a GoTrue API client whose requests fail after `timeout` and whose
+    /// connection attempts fail after `connect_timeout`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    /// use std::time::Duration;
+    ///
+    /// let client = Api::with_timeouts(
+    ///     "http://your.gotrue.endpoint".to_string(),
+    ///     Duration::from_secs(10),
+    ///     Duration::from_secs(2),
+    /// );
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_timeouts(url: String, timeout: Duration, connect_timeout: Duration) -> Api {
+        let client = client_builder()
+            .timeout(timeout)
+            .connect_timeout(connect_timeout)
+            .build()
+            .expect("Failed to build HTTP client.");
+
+        Api::new_with_client(url, client)
+    }
+
+    /// Add arbitrary headers to the request. For instance when you may want to connect
+    /// through an API gateway that needs an API key header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// let client = Api::new("https://your.gotrue.endpoint".to_string())
+    ///     .insert_header("apikey", "super.secret.key");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `header_value` isn't a valid header value, e.g. because it contains a newline.
+    /// Use [`Api::try_insert_header`] for values that aren't known to be valid.
+    pub fn insert_header(
+        self,
+        header_name: impl IntoHeaderName,
+        header_value: impl AsRef<str>,
+    ) -> Self {
+        self.try_insert_header(header_name, header_value)
+            .expect("Invalid header value.")
+    }
+
+    /// Add arbitrary headers to the request, returning an error if `header_value` isn't a
+    /// valid header value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// let api_key = std::env::var("GOTRUE_API_KEY").unwrap_or_default();
+    /// let client = Api::new("https://your.gotrue.endpoint".to_string())
+    ///     .try_insert_header("apikey", api_key);
+    /// ```
+    pub fn try_insert_header(
+        mut self,
+        header_name: impl IntoHeaderName,
+        header_value: impl AsRef<str>,
+    ) -> Result<Self, InvalidHeaderValue> {
+        self.headers
+            .insert(header_name, HeaderValue::from_str(header_value.as_ref())?);
+        Ok(self)
+    }
+
+    /// Returns the url of the GoTrue server without trailing slash, e.g. to build a request to an
+    /// endpoint the crate doesn't support yet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// let client = Api::new("https://your.gotrue.endpoint/auth/v1/".to_string());
+    ///
+    /// assert_eq!(client.url(), "https://your.gotrue.endpoint/auth/v1");
+    /// ```
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the headers sent with every request, like `apikey`.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Sets the url users are sent back to from signup confirmation, magic link, recovery, invite
+    /// and OAuth flows when no `redirect_to` is given for a single call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// let client = Api::new("http://your.gotrue.endpoint".to_string())
+    ///     .with_redirect_to("https://example.com/welcome");
+    /// ```
+    pub fn with_redirect_to(mut self, redirect_to: impl Into<String>) -> Self {
+        self.redirect_to = Some(redirect_to.into());
+        self
+    }
+
+    /// Builds requests without sending them. Every call fails with [`ApiError::DryRun`] carrying
+    /// the request that would have been sent, e.g. to inspect its url, headers and body in tests.
+    ///
+    /// Methods sending several requests fail with the first one, e.g.
+    /// [`Api::update_user_metadata`] with `merge` returns the `GET /user` reading the current
+    /// metadata and [`AdminApi::get_user_by_email`](crate::AdminApi::get_user_by_email) the
+    /// request for the first page.
+    ///
+    /// A [`Client`](crate::Client) using this api returns the request as
+    /// `Error::Api(ApiError::DryRun(_))` and keeps its current session untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{error::ApiError, Api, EmailOrPhone};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Api::new("http://your.gotrue.endpoint".to_string()).with_dry_run();
+    ///
+    ///     let email = "email@example.com".to_string();
+    ///     let result = client.sign_in(EmailOrPhone::Email(email), "Abcd1234!").await;
+    ///
+    ///     if let Err(ApiError::DryRun(request)) = result {
+    ///         assert_eq!(request.url().path(), "/token");
+    ///     }
+    /// }
+    /// ```
+    pub fn with_dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Retries idempotent requests on transient failures according to `retry`.
+    /// Requests that create or change data, like `sign_up`, are never retried.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, RetryConfig};
+    ///
+    /// let client = Api::new("http://your.gotrue.endpoint".to_string())
+    ///     .with_retry(RetryConfig::default());
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Signs up for a new account
+    ///
+    /// Returns [`SignUpResult::ConfirmationRequired`] if the user has to confirm their email or
+    /// phone before signing in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, EmailOrPhone};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let mut client = Api::new(url);
+    ///
+    ///     let email = "email@example.com".to_string();
+    ///     let password = "Abcd1234!".to_string();
+    ///
+    ///     let result = client.sign_up(EmailOrPhone::Email(email), &password).await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sign_up(
+        &self,
+        email_or_phone: EmailOrPhone,
+        password: &str,
+    ) -> Result<SignUpResult, ApiError> {
+        self.signup(email_or_phone, password, SignUpOptions::default(), None)
+            .await
+    }
+
+    /// Signs up for a new account on a server with CAPTCHA protection enabled
+    ///
+    /// `captcha_token` is the token the hCaptcha or Turnstile widget returned to the user.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, EmailOrPhone};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Api::new("http://localhost:9998".to_string());
+    ///
+    ///     let email = "email@example.com".to_string();
+    ///     let result = client
+    ///         .sign_up_with_captcha(EmailOrPhone::Email(email), "Abcd1234!", "captcha-token")
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn sign_up_with_captcha(
+        &self,
+        email_or_phone: EmailOrPhone,
+        password: &str,
+        captcha_token: &str,
+    ) -> Result<SignUpResult, ApiError> {
+        let options = SignUpOptions {
+            captcha_token: Some(captcha_token.to_string()),
+            ..Default::default()
+        };
+
+        self.signup(email_or_phone, password, options, None).await
+    }
+
+    /// Signs up for a new account with user metadata, a redirect url for the confirmation link or
+    /// a CAPTCHA token
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, EmailOrPhone, SignUpOptions};
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Api::new("http://localhost:9998".to_string());
+    ///
+    ///     let options = SignUpOptions {
+    ///         data: Some(json!({ "display_name": "Jane" })),
+    ///         redirect_to: Some("https://example.com/welcome".to_string()),
+    ///         ..Default::default()
+    ///     };
+    ///     let email = "email@example.com".to_string();
+    ///     let result = client
+    ///         .sign_up_with(EmailOrPhone::Email(email), "Abcd1234!", options)
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn sign_up_with(
+        &self,
+        email_or_phone: EmailOrPhone,
+        password: &str,
+        options: SignUpOptions,
+    ) -> Result<SignUpResult, ApiError> {
+        self.signup(email_or_phone, password, options, None).await
+    }
+
+    /// Signs up for a new account, sending `idempotency_key` as `Idempotency-Key` header
+    ///
+    /// The request is retried like idempotent requests if a [`RetryConfig`] is set, so use a
+    /// key that is unique per sign up attempt, e.g. a random UUID. GoTrue itself doesn't
+    /// deduplicate requests, the header must be honored by a gateway in front of it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, EmailOrPhone};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Api::new("http://localhost:9998".to_string());
+    ///
+    ///     let email = "email@example.com".to_string();
+    ///     let result = client
+    ///         .sign_up_with_idempotency_key(EmailOrPhone::Email(email), "Abcd1234!", "6f1c6f0e")
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn sign_up_with_idempotency_key(
+        &self,
+        email_or_phone: EmailOrPhone,
+        password: &str,
+        idempotency_key: &str,
+    ) -> Result<SignUpResult, ApiError> {
+        let options = SignUpOptions::default();
+
+        self.signup(email_or_phone, password, options, Some(idempotency_key))
+            .await
+    }
+
+    pub(crate) async fn signup(
+        &self,
+        email_or_phone: EmailOrPhone,
+        password: &str,
+        options: SignUpOptions,
+        idempotency_key: Option<&str>,
+    ) -> Result<SignUpResult, ApiError> {
+        let endpoint = format!("{}/signup", self.url);
+
+        let mut body = match email_or_phone {
+            EmailOrPhone::Email(email) => json!({
+                "email": email,
+                "password": &password,
+            }),
+            EmailOrPhone::Phone(phone) => json!({
+                "phone": phone,
+                "password": &password
+            }),
+        };
+        if let Some(data) = options.data {
+            body["data"] = data;
+        }
+        let body = with_captcha_token(body, options.captcha_token.as_deref());
+
+        let mut request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        if let Some(redirect_to) = options
+            .redirect_to
+            .as_deref()
+            .or(self.redirect_to.as_deref())
+        {
+            request = request.query(&[("redirect_to", redirect_to)]);
+        }
+        let response = self
+            .send_with_idempotency_key(request, idempotency_key)
+            .await?;
+
+        parse_json(response).await
+    }
+
+    /// Signs in as a new anonymous user, optionally attaching user metadata
+    ///
+    /// The anonymous user can later be converted into a permanent one by adding an email or phone.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let result = client
+    ///         .sign_in_anonymously(Some(json!({ "theme": "dark" })))
+    ///         .await;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sign_in_anonymously(
+        &self,
+        data: Option<serde_json::Value>,
+    ) -> Result<Session, ApiError> {
+        let endpoint = format!("{}/signup", self.url);
+
+        let body = json!({
+            "data": data.unwrap_or_else(|| json!({})),
+        });
+
+        let request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        let response = self.send(request).await?;
+
+        parse_json(response).await
+    }
+
+    /// Signs into an existing account
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, EmailOrPhone};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let mut client = Api::new(url);
+    ///
+    ///     let email = "email@example.com".to_string();
+    ///     let password = "Abcd1234!".to_string();
+    ///
+    ///     let result = client.sign_in(EmailOrPhone::Email(email), &password).await;
+    ///     
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sign_in(
+        &self,
+        email_or_phone: EmailOrPhone,
+        password: &str,
+    ) -> Result<Session, ApiError> {
+        self.password_sign_in(email_or_phone, password, None).await
+    }
+
+    /// Signs into an existing account on a server with CAPTCHA protection enabled
+    ///
+    /// `captcha_token` is the token the hCaptcha or Turnstile widget returned to the user.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, EmailOrPhone};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Api::new("http://localhost:9998".to_string());
+    ///
+    ///     let email = "email@example.com".to_string();
+    ///     let result = client
+    ///         .sign_in_with_captcha(EmailOrPhone::Email(email), "Abcd1234!", "captcha-token")
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn sign_in_with_captcha(
+        &self,
+        email_or_phone: EmailOrPhone,
+        password: &str,
+        captcha_token: &str,
+    ) -> Result<Session, ApiError> {
+        self.password_sign_in(email_or_phone, password, Some(captcha_token))
+            .await
+    }
+
+    pub(crate) async fn password_sign_in(
+        &self,
+        email_or_phone: EmailOrPhone,
+        password: &str,
+        captcha_token: Option<&str>,
+    ) -> Result<Session, ApiError> {
+        let body = match email_or_phone {
+            EmailOrPhone::Email(email) => json!({
+                "email": email,
+                "password": &password,
+            }),
+            EmailOrPhone::Phone(phone) => json!({
+                "phone": phone,
+                "password": &password
+            }),
+        };
+        let body = with_captcha_token(body, captcha_token);
+
+        self.token(GrantType::Password, &body).await
+    }
+
+    /// Sends an OTP Code and creates user if it does not exist
+    ///
+    /// GoTrue enforces a cooldown between sends to the same address. Sending again too early fails
+    /// with [`ApiError::RateLimited`], whose `retry_after` tells how long to wait.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, EmailOrPhone};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let mut client = Api::new(url);
+    ///
+    ///     let email = "email@example.com".to_string();
+    ///
+    ///     let result = client.send_otp(EmailOrPhone::Email(email), None).await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn send_otp(
+        &self,
+        email_or_phone: EmailOrPhone,
+        should_create_user: Option<bool>,
+    ) -> Result<bool, ApiError> {
+        let params = OtpParams {
+            should_create_user,
+            ..Default::default()
+        };
+
+        self.otp(email_or_phone, params).await
+    }
+
+    /// Sends an OTP Code on a server with CAPTCHA protection enabled, see [`Api::send_otp`]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, EmailOrPhone};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Api::new("http://localhost:9998".to_string());
+    ///
+    ///     let email = "email@example.com".to_string();
+    ///     let result = client
+    ///         .send_otp_with_captcha(EmailOrPhone::Email(email), None, "captcha-token")
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn send_otp_with_captcha(
+        &self,
+        email_or_phone: EmailOrPhone,
+        should_create_user: Option<bool>,
+        captcha_token: &str,
+    ) -> Result<bool, ApiError> {
+        let params = OtpParams {
+            should_create_user,
+            captcha_token: Some(captcha_token.to_string()),
+            ..Default::default()
+        };
+
+        self.otp(email_or_phone, params).await
+    }
+
+    /// Sends an OTP Code with metadata, a delivery channel or a redirect url
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, EmailOrPhone, OtpChannel, OtpParams};
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Api::new("http://localhost:9998".to_string());
+    ///
+    ///     let params = OtpParams {
+    ///         channel: Some(OtpChannel::Whatsapp),
+    ///         data: Some(json!({ "referrer": "newsletter" })),
+    ///         ..Default::default()
+    ///     };
+    ///     let phone = "+4915112345678".to_string();
+    ///     let result = client.send_otp_with(EmailOrPhone::Phone(phone), params).await;
+    /// }
+    /// ```
+    pub async fn send_otp_with(
+        &self,
+        email_or_phone: EmailOrPhone,
+        params: OtpParams,
+    ) -> Result<bool, ApiError> {
+        self.otp(email_or_phone, params).await
+    }
+
+    pub(crate) async fn otp(
+        &self,
+        email_or_phone: EmailOrPhone,
+        params: OtpParams,
+    ) -> Result<bool, ApiError> {
+        let endpoint = format!("{}/otp", self.url);
+
+        let mut body = match email_or_phone {
+            EmailOrPhone::Email(email) => json!({
+                "email": email,
+                "should_create_user": Some(params.should_create_user)
+            }),
+            EmailOrPhone::Phone(phone) => json!({
+                "phone": phone,
+                "should_create_user": Some(params.should_create_user)
+            }),
+        };
+        if let Some(data) = params.data {
+            body["data"] = data;
+        }
+        if let Some(channel) = params.channel {
+            body["channel"] = json!(channel);
+        }
+        let body = with_captcha_token(body, params.captcha_token.as_deref());
+
+        let mut request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        if let Some(redirect_to) = params
+            .redirect_to
+            .as_deref()
+            .or(self.redirect_to.as_deref())
+        {
+            request = request.query(&[("redirect_to", redirect_to)]);
+        }
+        self.send(request).await?;
+
+        Ok(true)
+    }
+
+    /// Sends a magic link to the given email address, creating the user if it does not exist.
+    /// The link returns the user to `redirect_to` when given, otherwise to the site URL.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let result = client
+    ///         .sign_in_with_magic_link("email@example.com", Some("https://example.com/welcome"))
+    ///         .await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sign_in_with_magic_link(
+        &self,
+        email: &str,
+        redirect_to: Option<&str>,
+    ) -> Result<bool, ApiError> {
+        let endpoint = format!("{}/otp", self.url);
+
+        let body = json!({
+            "email": email,
+            "create_user": true,
+        });
+
+        let mut request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        if let Some(redirect_to) = redirect_to.or(self.redirect_to.as_deref()) {
+            request = request.query(&[("redirect_to", redirect_to)]);
+        }
+        self.send(request).await?;
+
+        Ok(true)
+    }
+
+    /// Resends a signup confirmation, email change or OTP message
+    ///
+    /// Like [`Api::send_otp`] this fails with [`ApiError::RateLimited`] during the cooldown.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, EmailOrPhone, ResendType};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let email = "email@example.com".to_string();
+    ///
+    ///     let result = client
+    ///         .resend(EmailOrPhone::Email(email), ResendType::Signup)
+    ///         .await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn resend(
+        &self,
+        email_or_phone: EmailOrPhone,
+        resend_type: ResendType,
+    ) -> Result<bool, ApiError> {
+        let endpoint = format!("{}/resend", self.url);
+
+        let body = match email_or_phone {
+            EmailOrPhone::Email(email) => json!({
+                "email": email,
+                "type": resend_type,
+            }),
+            EmailOrPhone::Phone(phone) => json!({
+                "phone": phone,
+                "type": resend_type,
+            }),
+        };
+
+        let request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        self.send(request).await?;
+
+        Ok(true)
+    }
+
+    /// Verifies an OTP, magic link or token hash and returns the resulting session
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, OtpType, VerifyOtpParams};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let params = VerifyOtpParams::Email {
+    ///         email: "email@example.com".to_string(),
+    ///         token: "123456".to_string(),
+    ///         otp_type: OtpType::Magiclink,
+    ///     };
+    ///     let result = client.verify_otp_with(params).await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn verify_otp_with(&self, params: VerifyOtpParams) -> Result<Session, ApiError> {
+        self.verify_otp(params).await
+    }
+
+    /// Verifies the `token_hash` of an email link, e.g. a signup confirmation, and returns the
+    /// resulting session
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, OtpType};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     // `token_hash` and `type` are read from the query of the confirmation link
+    ///     let result = client.verify_token_hash("token_hash", OtpType::Signup).await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn verify_token_hash(
+        &self,
+        token_hash: &str,
+        otp_type: OtpType,
+    ) -> Result<Session, ApiError> {
+        let params = VerifyOtpParams::TokenHash {
+            token_hash: token_hash.to_string(),
+            otp_type,
+        };
+
+        self.verify_otp_with(params).await
+    }
+
+    /// Verifies an OTP or magic link token and returns the resulting session.
+    /// Prefer [`Api::verify_otp_with`], this accepts any body and is meant as an escape hatch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let params = json!({ "email": "email@example.com", "token": "123456", "type": "magiclink" });
+    ///     let result = client.verify_otp(params).await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn verify_otp<T: serde::Serialize>(&self, params: T) -> Result<Session, ApiError> {
+        let endpoint = format!("{}/verify", self.url);
+
+        let body = serde_json::to_value(&params)?;
+
+        let request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        let response = self.send(request).await?;
+
+        parse_json(response).await
+    }
+
+    /// Confirms an email change with the token sent to the current or the new address
+    ///
+    /// Call this once per address. With secure email change enabled the first call returns
+    /// [`EmailChangeResult::Pending`] until the other address is confirmed as well.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, EmailChangeResult};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let result = client
+    ///         .verify_email_change("new-email@example.com", "123456")
+    ///         .await;
+    ///
+    ///     if let Ok(EmailChangeResult::Pending { msg }) = result {
+    ///         println!("{msg}");
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn verify_email_change(
+        &self,
+        email: &str,
+        token: &str,
+    ) -> Result<EmailChangeResult, ApiError> {
+        let endpoint = format!("{}/verify", self.url);
+
+        let body = json!({
+            "email": email,
+            "token": token,
+            "type": OtpType::EmailChange,
+        });
+
+        let request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        let response = self.send(request).await?;
+
+        parse_json(response).await
+    }
+
+    /// Confirms a phone number change with the OTP sent to the new number
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let result = client.verify_phone_change("+4915112345678", "123456").await;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn verify_phone_change(&self, phone: &str, token: &str) -> Result<Session, ApiError> {
+        self.verify_otp_with(VerifyOtpParams::Phone {
+            phone: phone.to_string(),
+            token: token.to_string(),
+            otp_type: OtpType::PhoneChange,
+        })
+        .await
+    }
+
+    /// Signs the current user out
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, EmailOrPhone};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let mut client = Api::new(url);
+    ///
+    ///
+    ///     let email = "email@example.com".to_string();
+    ///     let password = "Abcd1234!".to_string();
+    ///
+    ///     let session = client.sign_in(EmailOrPhone::Email(email), &password).await?;
+    ///     client.sign_out(&session.access_token);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sign_out(&self, access_token: &str) -> Result<bool, ApiError> {
+        self.logout(access_token, None).await
+    }
+
+    /// Signs the user out of the sessions selected by `scope`, e.g. of all devices
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, SignOutScope};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Api::new("http://localhost:9998".to_string());
+    ///
+    ///     let result = client
+    ///         .sign_out_with_scope("access_token", SignOutScope::Others)
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn sign_out_with_scope(
+        &self,
+        access_token: &str,
+        scope: SignOutScope,
+    ) -> Result<bool, ApiError> {
+        self.logout(access_token, Some(scope)).await
+    }
+
+    async fn logout(
+        &self,
+        access_token: &str,
+        scope: Option<SignOutScope>,
+    ) -> Result<bool, ApiError> {
+        let endpoint = format!("{}/logout", self.url);
+
+        let mut request = self.authorized_request(Method::POST, endpoint, access_token);
+        if let Some(scope) = scope {
+            request = request.query(&[("scope", scope.as_str())]);
+        }
+        self.send(request).await?;
+
+        Ok(true)
+    }
+
+    /// Sends password recovery email
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, EmailOrPhone};
+    ///
+    /// let url = "http://localhost:9998".to_string();
+    /// let mut client = Api::new(url);
+    /// let email = "random@mail.com".to_string();
+    ///
+    /// client.reset_password_for_email(&email);
+    /// ```
+    pub async fn reset_password_for_email(&self, email: &str) -> Result<bool, ApiError> {
+        self.recover(email, None).await
+    }
+
+    /// Sends password recovery email on a server with CAPTCHA protection enabled
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Api::new("http://localhost:9998".to_string());
+    ///
+    ///     let result = client
+    ///         .reset_password_for_email_with_captcha("email@example.com", "captcha-token")
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn reset_password_for_email_with_captcha(
+        &self,
+        email: &str,
+        captcha_token: &str,
+    ) -> Result<bool, ApiError> {
+        self.recover(email, Some(captcha_token)).await
+    }
+
+    pub(crate) async fn recover(
+        &self,
+        email: &str,
+        captcha_token: Option<&str>,
+    ) -> Result<bool, ApiError> {
+        let endpoint = format!("{}/recover", self.url);
+
+        let body = json!({
+            "email": &email,
+        });
+        let body = with_captcha_token(body, captcha_token);
+
+        let mut request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        if let Some(redirect_to) = &self.redirect_to {
+            request = request.query(&[("redirect_to", redirect_to)]);
+        }
+        self.send(request).await?;
+
+        Ok(true)
+    }
+
+    /// Returns the authorization URL for a provider
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, Provider};
+    ///
+    /// let client = Api::new("http://localhost:9998".to_string());
+    /// let url = client.get_url_for_provider(Provider::Github);
+    /// ```
+    pub fn get_url_for_provider(&self, provider: impl Into<Provider>) -> String {
+        self.get_url_for_provider_with_options(provider, OAuthOptions::default())
+    }
+
+    /// Returns the authorization URL for a provider including redirect target, scopes and
+    /// additional query parameters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, OAuthOptions};
+    ///
+    /// let client = Api::new("http://localhost:9998".to_string());
+    /// let url = client.get_url_for_provider_with_options(
+    ///     "google",
+    ///     OAuthOptions {
+    ///         redirect_to: Some("https://example.com/callback".to_string()),
+    ///         scopes: Some("https://www.googleapis.com/auth/calendar.readonly".to_string()),
+    ///         query_params: vec![("access_type".to_string(), "offline".to_string())],
+    ///         ..Default::default()
+    ///     },
+    /// );
+    /// ```
+    pub fn get_url_for_provider_with_options(
+        &self,
+        provider: impl Into<Provider>,
+        options: OAuthOptions,
+    ) -> String {
+        let query = self.oauth_query(&provider.into(), &options).finish();
+
+        format!("{}/authorize?{}", self.url, query)
+    }
+
+    fn oauth_query(
+        &self,
+        provider: &Provider,
+        options: &OAuthOptions,
+    ) -> form_urlencoded::Serializer<'static, String> {
+        let mut query = form_urlencoded::Serializer::new(String::new());
+        query.append_pair("provider", provider.as_str());
+
+        if let Some(redirect_to) = options.redirect_to.as_ref().or(self.redirect_to.as_ref()) {
+            query.append_pair("redirect_to", redirect_to);
+        }
+        if let Some(scopes) = &options.scopes {
+            query.append_pair("scopes", scopes);
+        }
+        for (name, value) in &options.query_params {
+            query.append_pair(name, value);
+        }
+
+        query
+    }
+
+    /// Returns the authorization URL to link an additional provider to the signed in user
+    ///
+    /// Send the user to the returned URL. Once they signed in with the provider, GoTrue adds the
+    /// new identity to the user the access token belongs to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, OAuthOptions};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let result = client
+    ///         .link_identity("access_token", "github", OAuthOptions::default())
+    ///         .await;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn link_identity(
+        &self,
+        access_token: &str,
+        provider: impl Into<Provider>,
+        options: OAuthOptions,
+    ) -> Result<String, ApiError> {
+        let mut query = self.oauth_query(&provider.into(), &options);
+        query.append_pair("skip_http_redirect", "true");
+        let endpoint = format!("{}/user/identities/authorize?{}", self.url, query.finish());
+
+        let request = self.authorized_request(Method::GET, endpoint, access_token);
+        let response = self.send(request).await?;
+        let body: AuthorizeUrl = parse_json(response).await?;
+
+        Ok(body.url)
+    }
+
+    /// Returns the authorization URL for the PKCE flow together with the generated
+    /// [`PkceChallenge`]. Keep its `code_verifier` to call [`Api::exchange_code_for_session`]
+    /// once the provider redirected back with an auth code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// let client = Api::new("http://localhost:9998".to_string());
+    /// let (url, pkce) = client.get_url_for_provider_with_pkce("github");
+    /// ```
+    pub fn get_url_for_provider_with_pkce(
+        &self,
+        provider: impl Into<Provider>,
+    ) -> (String, PkceChallenge) {
+        let pkce = PkceChallenge::new();
+
+        let url = format!(
+            "{}&code_challenge={}&code_challenge_method=S256",
+            self.get_url_for_provider(provider),
+            pkce.code_challenge
+        );
+
+        (url, pkce)
+    }
+
+    /// Starts a SAML single sign-on and returns the URL of the identity provider the browser
+    /// should be redirected to
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, SsoParams};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let result = client
+    ///         .sign_in_with_sso(SsoParams::for_domain("example.com"))
+    ///         .await;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sign_in_with_sso(&self, mut params: SsoParams) -> Result<String, ApiError> {
+        let endpoint = format!("{}/sso", self.url);
+
+        if params.redirect_to.is_none() {
+            params.redirect_to = self.redirect_to.clone();
+        }
+        let body = SsoRequest {
+            params: &params,
+            skip_http_redirect: true,
+        };
+
+        let request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        let response = self.send(request).await?;
+        let body: AuthorizeUrl = parse_json(response).await?;
+
+        Ok(body.url)
+    }
+
+    /// Returns the authorization URL for a provider together with the PKCE code verifier needed
+    /// to exchange the auth code of the callback for a session
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, OAuthOptions, Provider};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let client = Api::new("http://localhost:9998".to_string());
+    ///
+    ///     let oauth = client.sign_in_with_oauth(Provider::Github, OAuthOptions::default());
+    ///
+    ///     // Redirect the user to `oauth.url` and read the auth code from the callback
+    ///     let auth_code = "auth_code";
+    ///
+    ///     if let Some(code_verifier) = &oauth.code_verifier {
+    ///         let result = client
+    ///             .exchange_code_for_session(auth_code, code_verifier)
+    ///             .await;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn sign_in_with_oauth(
+        &self,
+        provider: impl Into<Provider>,
+        options: OAuthOptions,
+    ) -> OAuthResponse {
+        let skip_pkce = options.skip_pkce;
+        let url = self.get_url_for_provider_with_options(provider, options);
+
+        if skip_pkce {
+            return OAuthResponse {
+                url,
+                code_verifier: None,
+            };
+        }
+
+        let pkce = PkceChallenge::new();
+        OAuthResponse {
+            url: format!(
+                "{}&code_challenge={}&code_challenge_method=S256",
+                url, pkce.code_challenge
+            ),
+            code_verifier: Some(pkce.code_verifier),
+        }
+    }
+
+    /// Exchanges the auth code of a PKCE flow for a session
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let (url, pkce) = client.get_url_for_provider_with_pkce("github");
+    ///
+    ///     // Redirect the user to `url` and read the auth code from the callback
+    ///     let auth_code = "auth_code";
+    ///
+    ///     let result = client
+    ///         .exchange_code_for_session(auth_code, &pkce.code_verifier)
+    ///         .await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn exchange_code_for_session(
+        &self,
+        auth_code: &str,
+        code_verifier: &str,
+    ) -> Result<Session, ApiError> {
+        let body = json!({ "auth_code": auth_code, "code_verifier": code_verifier });
+
+        self.token(GrantType::Pkce, &body).await
+    }
+
+    /// Signs in with an ID token obtained from a provider's native SDK, e.g. Sign in with Apple
+    /// or Google Sign-In on mobile
+    ///
+    /// Returns [`ApiError::InvalidParams`] without sending a request if the provider doesn't
+    /// support ID tokens.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, IdTokenParams, Provider};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let params = IdTokenParams {
+    ///         provider: Provider::Apple,
+    ///         token: "id.token.from-apple-sdk".to_string(),
+    ///         access_token: None,
+    ///         nonce: Some("raw-nonce".to_string()),
+    ///     };
+    ///     let result = client.sign_in_with_id_token(params).await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sign_in_with_id_token(&self, params: IdTokenParams) -> Result<Session, ApiError> {
+        if !params.provider.supports_id_token() {
+            return Err(ApiError::InvalidParams(format!(
+                "provider {} does not support ID token sign in",
+                params.provider
+            )));
+        }
+
+        self.token(GrantType::IdToken, &params).await
+    }
+
+    /// Refreshes the current session by refresh token
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, EmailOrPhone};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let mut client = Api::new(url);
+    ///
+    ///
+    ///     let email = "email@example.com".to_string();
+    ///     let password = "Abcd1234!".to_string();
+    ///
+    ///     let session = client.sign_in(EmailOrPhone::Email(email), &password).await?;
+    ///     client.refresh_access_token(&session.refresh_token);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn refresh_access_token(&self, refresh_token: &str) -> Result<Session, ApiError> {
+        let body = json!({ "refresh_token": refresh_token });
+
+        self.token(GrantType::RefreshToken, &body).await
+    }
+
+    /// Exchanges the credentials in `body` for a session at `/token`. Refresh token requests are
+    /// retried like other idempotent requests, see [`Api::with_retry`].
+    async fn token<T: Serialize>(
+        &self,
+        grant_type: GrantType,
+        body: &T,
+    ) -> Result<Session, ApiError> {
+        let endpoint = format!("{}/token", self.url);
+
+        let request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .query(&[("grant_type", grant_type.as_str())])
+            .json(body);
+        let response = match grant_type {
+            GrantType::RefreshToken => self.send_idempotent(request).await?,
+            _ => self.send(request).await?,
+        };
+
+        parse_json(response).await
+    }
+
+    /// Gets a user by access token
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, EmailOrPhone};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let mut client = Api::new(url);
+    ///
+    ///
+    ///     let email = "email@example.com".to_string();
+    ///     let password = "Abcd1234!".to_string();
+    ///
+    ///     let session = client.sign_in(EmailOrPhone::Email(email), &password).await?;
+    ///     let user = client.get_user(&session.access_token);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_user(&self, jwt: &str) -> Result<User, ApiError> {
+        let endpoint = format!("{}/user", self.url);
+
+        let request = self.authorized_request(Method::GET, endpoint, jwt);
+        let response = self.send_idempotent(request).await?;
+
+        parse_json(response).await
+    }
+
+    /// Updates a user
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, UserAttributes};
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let attributes = UserAttributes {
+    ///         data: Some(json!({ "test": "test" })),
+    ///         ..Default::default()
+    ///     };
+    ///
+    ///     let result = client.update_user(attributes, "access_token").await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn update_user(
+        &self,
+        user: UserAttributes,
+        jwt: &str,
+    ) -> Result<UserUpdate, ApiError> {
+        let endpoint = format!("{}/user", self.url);
+
+        let request = self
+            .authorized_request(Method::PUT, endpoint, jwt)
+            .json(&user);
+        let response = self.send(request).await?;
+
+        parse_json(response).await
+    }
+
+    /// Changes the password of the signed in user
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let result = client.update_password("access_token", "Abcd12345!").await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn update_password(
+        &self,
+        access_token: &str,
+        new_password: &str,
+    ) -> Result<UserUpdate, ApiError> {
+        let attributes = UserAttributes {
+            password: Some(new_password.to_string()),
+            ..Default::default()
+        };
+
+        self.update_user(attributes, access_token).await
+    }
+
+    /// Starts changing the phone number of the signed in user
+    ///
+    /// GoTrue sends an OTP to `new_phone` and keeps the current number until it is confirmed
+    /// with [`Api::verify_phone_change`]. The returned [`UserUpdate::new_phone`] holds the
+    /// pending number.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let result = client.update_phone("access_token", "+4915112345678").await;
+    ///
+    ///     // after the user entered the OTP sent by SMS
+    ///     let result = client.verify_phone_change("+4915112345678", "123456").await;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn update_phone(
+        &self,
+        access_token: &str,
+        new_phone: &str,
+    ) -> Result<UserUpdate, ApiError> {
+        let attributes = UserAttributes {
+            phone: Some(new_phone.to_string()),
+            ..Default::default()
+        };
+
+        self.update_user(attributes, access_token).await
+    }
 
-impl Api {
-    /// Creates a GoTrue API client.
+    /// Updates the metadata of the signed in user
+    ///
+    /// With `merge` set the current metadata is fetched first and the top level keys of `patch`
+    /// are merged into it, so keys not contained in `patch` are kept. Otherwise `patch` is sent
+    /// as is. In [dry run](Api::with_dry_run) mode a merge returns the `GET` of the current
+    /// metadata, pass `merge: false` to inspect the `PUT`.
     ///
     /// # Example
     ///
     /// ```
     /// use go_true::Api;
+    /// use serde_json::json;
     ///
-    /// let client = Api::new("http://your.gotrue.endpoint".to_string());
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let result = client
+    ///         .update_user_metadata("access_token", json!({ "theme": "dark" }), true)
+    ///         .await;
+    ///     Ok(())
+    /// }
     /// ```
-    pub fn new(url: String) -> Api {
-        Api {
-            url,
-            headers: HeaderMap::new(),
-            client: reqwest::Client::new(),
-        }
+    pub async fn update_user_metadata(
+        &self,
+        access_token: &str,
+        patch: serde_json::Value,
+        merge: bool,
+    ) -> Result<UserUpdate, ApiError> {
+        let data = match patch {
+            serde_json::Value::Object(patch) if merge => {
+                let mut data = self.get_user_metadata(access_token).await?;
+                match &mut data {
+                    serde_json::Value::Object(data) => data.extend(patch),
+                    _ => data = serde_json::Value::Object(patch),
+                }
+                data
+            }
+            patch => patch,
+        };
+
+        let attributes = UserAttributes {
+            data: Some(data),
+            ..Default::default()
+        };
+
+        self.update_user(attributes, access_token).await
     }
 
-    pub fn new_with_client(url: String, client: reqwest::Client) -> Api {
-        Api {
-            url,
-            headers: HeaderMap::new(),
-            client,
-        }
+    async fn get_user_metadata(&self, access_token: &str) -> Result<serde_json::Value, ApiError> {
+        let endpoint = format!("{}/user", self.url);
+
+        let request = self.authorized_request(Method::GET, endpoint, access_token);
+        let response = self.send_idempotent(request).await?;
+        let user: UserMetadata = parse_json(response).await?;
+
+        Ok(user.user_metadata)
     }
 
-    /// Add arbitrary headers to the request. For instance when you may want to connect
-    /// through an API gateway that needs an API key header.
+    /// Sends a nonce to the email or phone of the signed in user
+    ///
+    /// The nonce has to be passed in [`UserAttributes::nonce`] to change the password when the
+    /// server enforces secure password changes.
     ///
     /// # Example
     ///
     /// ```
     /// use go_true::Api;
     ///
-    /// let client = Api::new("https://your.gotrue.endpoint".to_string())
-    ///     .insert_header("apikey", "super.secret.key");
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let result = client.reauthenticate("access_token").await;
+    ///
+    ///     Ok(())
+    /// }
     /// ```
-    pub fn insert_header(
-        mut self,
-        header_name: impl IntoHeaderName,
-        header_value: impl AsRef<str>,
-    ) -> Self {
-        self.headers.insert(
-            header_name,
-            HeaderValue::from_str(header_value.as_ref()).expect("Invalid header value."),
-        );
-        self
+    pub async fn reauthenticate(&self, access_token: &str) -> Result<(), ApiError> {
+        let endpoint = format!("{}/reauthenticate", self.url);
+
+        let request = self.authorized_request(Method::GET, endpoint, access_token);
+        self.send(request).await?;
+
+        Ok(())
     }
 
-    /// Signs up for a new account
+    /// Converts an anonymous user into a permanent one by attaching an email or phone and a password
+    ///
+    /// The user keeps its id, so data associated with the anonymous user stays associated.
+    /// GoTrue sends a confirmation to the new email or phone; once it is verified with
+    /// [`verify_otp`](Api::verify_otp) using [`OtpType::EmailChange`](crate::OtpType::EmailChange)
+    /// or [`OtpType::PhoneChange`](crate::OtpType::PhoneChange) the user is no longer anonymous.
     ///
     /// # Example
     ///
@@ -76,336 +1804,269 @@ impl Api {
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ///     let url = "http://localhost:9998".to_string();
-    ///     let mut client = Api::new(url);
+    ///     let client = Api::new(url);
     ///
-    ///     let email = "email@example.com".to_string();
-    ///     let password = "Abcd1234!".to_string();
+    ///     let email = EmailOrPhone::Email("email@example.com".to_string());
+    ///     let result = client
+    ///         .link_identity_to_anonymous("access_token", email, "Abcd1234!")
+    ///         .await;
     ///
-    ///     let result = client.sign_up(EmailOrPhone::Email(email), &password).await;
     ///     Ok(())
     /// }
     /// ```
-    pub async fn sign_up(
+    pub async fn link_identity_to_anonymous(
         &self,
+        access_token: &str,
         email_or_phone: EmailOrPhone,
-        password: &String,
-    ) -> Result<Session, reqwest::Error> {
-        let endpoint = format!("{}/signup", self.url);
+        password: &str,
+    ) -> Result<User, ApiError> {
+        let endpoint = format!("{}/user", self.url);
 
         let body = match email_or_phone {
             EmailOrPhone::Email(email) => json!({
                 "email": email,
-                "password": &password,
+                "password": password,
             }),
             EmailOrPhone::Phone(phone) => json!({
                 "phone": phone,
-                "password": &password
+                "password": password,
             }),
         };
 
-        let response: Session = self
-            .client
-            .post(endpoint)
-            .headers(self.headers.clone())
-            .json(&body)
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<Session>()
-            .await?;
+        let request = self
+            .authorized_request(Method::PUT, endpoint, access_token)
+            .json(&body);
+        let response = self.send(request).await?;
 
-        return Ok(response);
+        parse_json(response).await
     }
 
-    /// Signs into an existing account
+    /// Enrolls a new MFA factor for the signed in user
     ///
     /// # Example
     ///
     /// ```
-    /// use go_true::{Api, EmailOrPhone};
+    /// use go_true::{Api, FactorType, MfaEnrollParams};
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ///     let url = "http://localhost:9998".to_string();
-    ///     let mut client = Api::new(url);
+    ///     let client = Api::new(url);
     ///
-    ///     let email = "email@example.com".to_string();
-    ///     let password = "Abcd1234!".to_string();
+    ///     let params = MfaEnrollParams {
+    ///         factor_type: FactorType::Totp,
+    ///         friendly_name: Some("Phone".to_string()),
+    ///         issuer: None,
+    ///     };
+    ///     let result = client.mfa_enroll("access_token", params).await;
     ///
-    ///     let result = client.sign_in(EmailOrPhone::Email(email), &password).await;
-    ///     
     ///     Ok(())
     /// }
     /// ```
-    pub async fn sign_in(
+    pub async fn mfa_enroll(
         &self,
-        email_or_phone: EmailOrPhone,
-        password: &String,
-    ) -> Result<Session, reqwest::Error> {
-        let query_string = String::from("?grant_type=password");
-
-        let endpoint = format!("{}/token{}", self.url, query_string);
-
-        let body = match email_or_phone {
-            EmailOrPhone::Email(email) => json!({
-                "email": email,
-                "password": &password,
-            }),
-            EmailOrPhone::Phone(phone) => json!({
-                "phone": phone,
-                "password": &password
-            }),
-        };
+        access_token: &str,
+        params: MfaEnrollParams,
+    ) -> Result<MfaEnrollResponse, ApiError> {
+        let endpoint = format!("{}/factors", self.url);
 
-        let response: Session = self
-            .client
-            .post(endpoint)
-            .headers(self.headers.clone())
-            .json(&body)
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<Session>()
-            .await?;
+        let request = self
+            .authorized_request(Method::POST, endpoint, access_token)
+            .json(&params);
+        let response = self.send(request).await?;
 
-        return Ok(response);
+        parse_json(response).await
     }
 
-    /// Sends an OTP Code and creates user if it does not exist
+    /// Creates a challenge for an enrolled MFA factor
     ///
     /// # Example
     ///
     /// ```
-    /// use go_true::{Api, EmailOrPhone};
+    /// use go_true::Api;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ///     let url = "http://localhost:9998".to_string();
-    ///     let mut client = Api::new(url);
+    ///     let client = Api::new(url);
     ///
-    ///     let email = "email@example.com".to_string();
+    ///     let result = client.mfa_challenge("access_token", "factor_id").await;
     ///
-    ///     let result = client.send_otp(EmailOrPhone::Email(email), None).await;
     ///     Ok(())
     /// }
     /// ```
-    pub async fn send_otp(
+    pub async fn mfa_challenge(
         &self,
-        email_or_phone: EmailOrPhone,
-        should_create_user: Option<bool>,
-    ) -> Result<bool, reqwest::Error> {
-        let endpoint = format!("{}/otp", self.url);
-
-        let body = match email_or_phone {
-            EmailOrPhone::Email(email) => json!({
-                "email": email,
-                "should_create_user": Some(should_create_user)
-            }),
-            EmailOrPhone::Phone(phone) => json!({
-                "phone": phone,
-                "should_create_user": Some(should_create_user)
-            }),
-        };
+        access_token: &str,
+        factor_id: &str,
+    ) -> Result<MfaChallengeResponse, ApiError> {
+        let endpoint = format!("{}/factors/{}/challenge", self.url, factor_id);
 
-        self.client
-            .post(endpoint)
-            .headers(self.headers.clone())
-            .json(&body)
-            .send()
-            .await?
-            .error_for_status()?;
+        let request = self.authorized_request(Method::POST, endpoint, access_token);
+        let response = self.send(request).await?;
 
-        return Ok(true);
+        parse_json(response).await
     }
 
-    pub async fn verify_otp<T: serde::Serialize>(&self, params: T) -> Result<bool, reqwest::Error> {
-        let endpoint = format!("{}/verify", self.url);
+    /// Verifies a challenge with the code of the MFA factor. Returns a new session with the
+    /// elevated authenticator assurance level.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let challenge = client.mfa_challenge("access_token", "factor_id").await?;
+    ///     let session = client
+    ///         .mfa_verify("access_token", "factor_id", &challenge.id, "123456")
+    ///         .await;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn mfa_verify(
+        &self,
+        access_token: &str,
+        factor_id: &str,
+        challenge_id: &str,
+        code: &str,
+    ) -> Result<Session, ApiError> {
+        let endpoint = format!("{}/factors/{}/verify", self.url, factor_id);
 
-        let body = serde_json::to_value(&params).unwrap();
+        let body = json!({ "challenge_id": challenge_id, "code": code });
 
-        self.client
-            .post(endpoint)
-            .headers(self.headers.clone())
-            .json(&body)
-            .send()
-            .await?
-            .error_for_status()?;
+        let request = self
+            .authorized_request(Method::POST, endpoint, access_token)
+            .json(&body);
+        let response = self.send(request).await?;
 
-        return Ok(true);
+        parse_json(response).await
     }
 
-    /// Signs the current user out
+    /// Lists the MFA factors of the signed in user
     ///
     /// # Example
     ///
     /// ```
-    /// use go_true::{Api, EmailOrPhone};
+    /// use go_true::Api;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ///     let url = "http://localhost:9998".to_string();
-    ///     let mut client = Api::new(url);
-    ///
+    ///     let client = Api::new(url);
     ///
-    ///     let email = "email@example.com".to_string();
-    ///     let password = "Abcd1234!".to_string();
-    ///
-    ///     let session = client.sign_in(EmailOrPhone::Email(email), &password).await?;
-    ///     client.sign_out(&session.access_token);
+    ///     let factors = client.mfa_list_factors("access_token").await;
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn sign_out(&self, access_token: &String) -> Result<bool, reqwest::Error> {
-        let endpoint = format!("{}/logout", self.url);
-
-        let mut headers: HeaderMap = self.headers.clone();
-        let bearer = format!("Bearer {access_token}");
-        headers.insert(
-            "Authorization",
-            HeaderValue::from_str(bearer.as_ref()).expect("Invalid header value."),
-        );
+    pub async fn mfa_list_factors(&self, access_token: &str) -> Result<Vec<Factor>, ApiError> {
+        let endpoint = format!("{}/user", self.url);
 
-        self.client
-            .post(endpoint)
-            .headers(headers)
-            .send()
-            .await?
-            .error_for_status()?;
+        let request = self.authorized_request(Method::GET, endpoint, access_token);
+        let response = self.send_idempotent(request).await?;
+        let user: UserFactors = parse_json(response).await?;
 
-        return Ok(true);
+        Ok(user.factors.unwrap_or_default())
     }
 
-    /// Sends password recovery email
+    /// Removes an MFA factor of the signed in user
     ///
     /// # Example
     ///
     /// ```
-    /// use go_true::{Api, EmailOrPhone};
+    /// use go_true::Api;
     ///
-    /// let url = "http://localhost:9998".to_string();
-    /// let mut client = Api::new(url);
-    /// let email = "random@mail.com".to_string();
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
     ///
-    /// client.reset_password_for_email(&email);
+    ///     let result = client.mfa_unenroll("access_token", "factor_id").await;
+    ///
+    ///     Ok(())
+    /// }
     /// ```
-    pub async fn reset_password_for_email(&self, email: &str) -> Result<bool, reqwest::Error> {
-        let endpoint = format!("{}/recover", self.url);
-
-        let body = json!({
-            "email": &email,
-        });
+    pub async fn mfa_unenroll(&self, access_token: &str, factor_id: &str) -> Result<(), ApiError> {
+        let endpoint = format!("{}/factors/{}", self.url, factor_id);
 
-        self.client
-            .post(endpoint)
-            .headers(self.headers.clone())
-            .json(&body)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        return Ok(true);
-    }
+        let request = self.authorized_request(Method::DELETE, endpoint, access_token);
+        self.send(request).await?;
 
-    pub fn get_url_for_provider(&self, provider: &str) -> String {
-        return format!("{}/authorize?provider={}", self.url, provider);
+        Ok(())
     }
 
-    /// Refreshes the current session by refresh token
+    /// Lists the identities linked to the signed in user
     ///
     /// # Example
     ///
     /// ```
-    /// use go_true::{Api, EmailOrPhone};
+    /// use go_true::Api;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ///     let url = "http://localhost:9998".to_string();
-    ///     let mut client = Api::new(url);
+    ///     let client = Api::new(url);
     ///
-    ///
-    ///     let email = "email@example.com".to_string();
-    ///     let password = "Abcd1234!".to_string();
-    ///
-    ///     let session = client.sign_in(EmailOrPhone::Email(email), &password).await?;
-    ///     client.refresh_access_token(&session.refresh_token);
+    ///     let identities = client.get_identities("access_token").await;
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn refresh_access_token(
-        &self,
-        refresh_token: &str,
-    ) -> Result<Session, reqwest::Error> {
-        let endpoint = format!("{}/token?grant_type=refresh_token", self.url);
-        let body = json!({ "refresh_token": refresh_token });
+    pub async fn get_identities(&self, access_token: &str) -> Result<Vec<Identity>, ApiError> {
+        let endpoint = format!("{}/user", self.url);
 
-        let session: Session = self
-            .client
-            .post(endpoint)
-            .headers(self.headers.clone())
-            .json(&body)
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?;
+        let request = self.authorized_request(Method::GET, endpoint, access_token);
+        let response = self.send_idempotent(request).await?;
+        let user: UserIdentities = parse_json(response).await?;
 
-        return Ok(session);
+        Ok(user.identities.unwrap_or_default())
     }
 
-    /// Gets a user by access token
+    /// Unlinks an identity from the signed in user
+    ///
+    /// `identity_id` is the [`Identity::identity_id`] of the identity to remove. GoTrue refuses
+    /// to unlink the last identity of a user.
     ///
     /// # Example
     ///
     /// ```
-    /// use go_true::{Api, EmailOrPhone};
+    /// use go_true::Api;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ///     let url = "http://localhost:9998".to_string();
-    ///     let mut client = Api::new(url);
-    ///
-    ///
-    ///     let email = "email@example.com".to_string();
-    ///     let password = "Abcd1234!".to_string();
+    ///     let client = Api::new(url);
     ///
-    ///     let session = client.sign_in(EmailOrPhone::Email(email), &password).await?;
-    ///     let user = client.get_user(&session.access_token);
+    ///     let result = client.unlink_identity("access_token", "identity_id").await;
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn get_user(&self, jwt: &str) -> Result<User, reqwest::Error> {
-        let endpoint = format!("{}/user", self.url);
-
-        let mut headers: HeaderMap = self.headers.clone();
-        let bearer = format!("Bearer {jwt}");
-        headers.insert(
-            "Authorization",
-            HeaderValue::from_str(bearer.as_ref()).expect("Invalid header value."),
-        );
+    pub async fn unlink_identity(
+        &self,
+        access_token: &str,
+        identity_id: &str,
+    ) -> Result<(), ApiError> {
+        let endpoint = format!("{}/user/identities/{}", self.url, identity_id);
 
-        let user: User = self
-            .client
-            .get(endpoint)
-            .headers(headers)
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?;
+        let request = self.authorized_request(Method::DELETE, endpoint, access_token);
+        self.send(request).await?;
 
-        return Ok(user);
+        Ok(())
     }
 
-    /// Updates a user
+    /// Invites a user via email
     ///
     /// # Example
     ///
     /// ```
     /// use go_true::{Api, EmailOrPhone};
-    /// use serde_json::json;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -413,91 +2074,128 @@ impl Api {
     ///     let mut client = Api::new(url);
     ///
     ///     let email = "email@example.com".to_string();
-    ///     let password = "Abcd1234!".to_string();
     ///
-    ///     client.sign_up(EmailOrPhone::Email(email.clone()), &password)
-    ///         .await?;
-    ///     let session = client.sign_in(EmailOrPhone::Email(email), &password).await?;
+    ///     let user = client.invite_user_by_email(&email).await?;
     ///
-    ///     let new_email = "otheremail@example.com";
-    ///     let attributes = UserAttributes {
-    ///         email: new_email.clone(),
-    ///         password: "Abcd12345!".to_string(),
-    ///         data: json!({ "test": "test" }),
-    ///     };
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn invite_user_by_email(&self, email: &str) -> Result<User, ApiError> {
+        self.invite_user_by_email_with(email, None, None).await
+    }
+
+    /// Invites a user via email, storing `data` as the user metadata of the invited user. The
+    /// invite link returns the user to `redirect_to` when given, otherwise to the default
+    /// redirect target or the site URL.
     ///
-    ///     let updatedUser = client.update_user(attributes, &session.access_token).await?;
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    /// use serde_json::json;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let result = client
+    ///         .invite_user_by_email_with(
+    ///             "email@example.com",
+    ///             Some(json!({ "team": "engineering" })),
+    ///             Some("https://example.com/welcome"),
+    ///         )
+    ///         .await;
     ///     Ok(())
     /// }
     /// ```
-    pub async fn update_user(
+    pub async fn invite_user_by_email_with(
         &self,
-        user: UserAttributes,
-        jwt: &str,
-    ) -> Result<UserUpdate, reqwest::Error> {
-        let endpoint = format!("{}/user", self.url);
+        email: &str,
+        data: Option<serde_json::Value>,
+        redirect_to: Option<&str>,
+    ) -> Result<User, ApiError> {
+        self.invite(email, data, redirect_to, None).await
+    }
 
-        let mut headers: HeaderMap = self.headers.clone();
-        let bearer = format!("Bearer {jwt}");
-        headers.insert(
-            "Authorization",
-            HeaderValue::from_str(bearer.as_ref()).expect("Invalid header value."),
-        );
+    /// Invites a user via email, sending `idempotency_key` as `Idempotency-Key` header
+    ///
+    /// Retried like idempotent requests, see [`Api::sign_up_with_idempotency_key`].
+    pub async fn invite_user_by_email_with_idempotency_key(
+        &self,
+        email: &str,
+        idempotency_key: &str,
+    ) -> Result<User, ApiError> {
+        self.invite(email, None, None, Some(idempotency_key)).await
+    }
+
+    async fn invite(
+        &self,
+        email: &str,
+        data: Option<serde_json::Value>,
+        redirect_to: Option<&str>,
+        idempotency_key: Option<&str>,
+    ) -> Result<User, ApiError> {
+        let endpoint = format!("{}/invite", self.url);
 
-        let body = json!({"email": user.email, "password": user.password, "data": user.data});
+        let mut body = json!({
+            "email": &email,
+        });
+        if let Some(data) = data {
+            body["data"] = data;
+        }
 
-        let user: UserUpdate = self
+        let mut request = self
             .client
-            .put(endpoint)
-            .headers(headers)
-            .json(&body)
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<UserUpdate>()
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        if let Some(redirect_to) = redirect_to.or(self.redirect_to.as_deref()) {
+            request = request.query(&[("redirect_to", redirect_to)]);
+        }
+        let response = self
+            .send_with_idempotency_key(request, idempotency_key)
             .await?;
 
-        return Ok(user);
+        parse_json(response).await
     }
 
-    /// Invites a user via email
+    /// Generates an email action link without sending an email
     ///
     /// # Example
     ///
     /// ```
-    /// use go_true::{Api, EmailOrPhone};
+    /// use go_true::{Api, GenerateLinkParams, GenerateLinkType};
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ///     let url = "http://localhost:9998".to_string();
-    ///     let mut client = Api::new(url);
-    ///
-    ///     let email = "email@example.com".to_string();
+    ///     let client = Api::new(url);
     ///
-    ///     let user = client.invite_user_by_email(&email).await?;
+    ///     let params = GenerateLinkParams::new(GenerateLinkType::MagicLink, "email@example.com");
+    ///     let result = client.generate_link(params).await;
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn invite_user_by_email(&self, email: &str) -> Result<User, reqwest::Error> {
-        let endpoint = format!("{}/invite", self.url);
+    pub async fn generate_link(
+        &self,
+        mut params: GenerateLinkParams,
+    ) -> Result<GenerateLinkResponse, ApiError> {
+        let endpoint = format!("{}/admin/generate_link", self.url);
 
-        let body = json!({
-            "email": &email,
-        });
+        if params.redirect_to.is_none() {
+            params.redirect_to = self.redirect_to.clone();
+        }
 
-        let user: User = self
+        let request = self
             .client
             .post(endpoint)
             .headers(self.headers.clone())
-            .json(&body)
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<User>()
-            .await?;
+            .json(&params);
+        let response = self.send(request).await?;
 
-        return Ok(user);
+        parse_json(response).await
     }
 
     /// Lists all users based on a query string
@@ -527,23 +2225,107 @@ impl Api {
     pub async fn list_users(
         &self,
         query_string: Option<String>,
-    ) -> Result<UserList, reqwest::Error> {
+    ) -> Result<PaginatedUsers, ApiError> {
         let endpoint = match query_string {
             Some(query) => format!("{}/admin/users{}", self.url, query),
             None => format!("{}/admin/users", self.url),
         };
 
-        let users: UserList = self
+        let request = self.client.get(endpoint).headers(self.headers.clone());
+        let response = self.send_idempotent(request).await?;
+
+        parse_paginated_users(response).await
+    }
+
+    /// Lists users page by page
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, ListUsersParams};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let params = ListUsersParams::new().page(2).per_page(50);
+    ///     let users = client.list_users_with(params).await;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn list_users_with(
+        &self,
+        params: ListUsersParams,
+    ) -> Result<PaginatedUsers, ApiError> {
+        let endpoint = format!("{}/admin/users", self.url);
+
+        let request = self
             .client
             .get(endpoint)
             .headers(self.headers.clone())
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?;
+            .query(&params);
+        let response = self.send_idempotent(request).await?;
+
+        parse_paginated_users(response).await
+    }
+
+    /// Streams all users, fetching the following pages as needed. Starts at the page set in
+    /// `params` and stops on the last page.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures::StreamExt;
+    /// use go_true::{Api, ListUsersParams};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let mut users = Box::pin(client.list_users_stream(ListUsersParams::new().per_page(100)));
+    ///     while let Some(user) = users.next().await {
+    ///         // ...
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn list_users_stream(
+        &self,
+        params: ListUsersParams,
+    ) -> impl Stream<Item = Result<User, ApiError>> + '_ {
+        let pages = stream::unfold(Some(params), move |params| async move {
+            let params = params?;
+
+            match self.list_users_with(params.clone()).await {
+                Ok(page) => {
+                    let is_last_page = match (page.next_page, params.per_page) {
+                        (None, _) => true,
+                        (Some(_), Some(per_page)) => page.users.len() < per_page as usize,
+                        (Some(_), None) => page.users.is_empty(),
+                    };
+                    let next_params = match page.next_page {
+                        Some(next_page) if !is_last_page => Some(params.page(next_page)),
+                        _ => None,
+                    };
+
+                    Some((Ok(page.users), next_params))
+                }
+                Err(e) => Some((Err(e), None)),
+            }
+        });
 
-        return Ok(users);
+        pages.flat_map(|page| {
+            let users: Vec<Result<User, ApiError>> = match page {
+                Ok(users) => users.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            };
+
+            stream::iter(users)
+        })
     }
 
     /// Gets a user by id
@@ -561,29 +2343,30 @@ impl Api {
     ///     let email = "email@example.com".to_string();
     ///     let password = "Abcd1234!".to_string();
     ///
-    ///     let session = client
+    ///     let result = client
     ///         .sign_up(EmailOrPhone::Email(email), &password)
     ///         .await?;
     ///
-    ///     let user = client.get_user_by_id(&session.user.id).await?;
+    ///     let user = client.get_user_by_id(&result.user().id).await?;
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn get_user_by_id(&self, user_id: &str) -> Result<User, reqwest::Error> {
+    pub async fn get_user_by_id(&self, user_id: &str) -> Result<User, ApiError> {
+        self.get_user_by_id_as(user_id).await
+    }
+
+    /// Gets a user by id, deserialized into `T` to read fields [`User`] doesn't carry.
+    pub(crate) async fn get_user_by_id_as<T: DeserializeOwned>(
+        &self,
+        user_id: &str,
+    ) -> Result<T, ApiError> {
         let endpoint = format!("{}/admin/users/{}", self.url, user_id);
 
-        let user: User = self
-            .client
-            .get(endpoint)
-            .headers(self.headers.clone())
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?;
+        let request = self.client.get(endpoint).headers(self.headers.clone());
+        let response = self.send_idempotent(request).await?;
 
-        return Ok(user);
+        parse_json(response).await
     }
 
     /// Creates a user
@@ -591,43 +2374,57 @@ impl Api {
     /// # Example
     ///
     /// ```
-    /// use go_true::{Api};
+    /// use go_true::{AdminUserAttributes, Api};
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ///     let url = "http://localhost:9998".to_string();
-    ///     let mut client = Api::new(url);
+    ///     let client = Api::new(url);
     ///
     ///     let user = AdminUserAttributes {
-    ///         email: "createemail@example.com",
+    ///         email: Some(String::from("createemail@example.com")),
     ///         password: Some(String::from("Abcd1234!")),
-    ///         data: None,
-    ///         email_confirmed: None,
-    ///         phone_confirmed: None,
+    ///         email_confirm: Some(true),
+    ///         ..Default::default()
     ///     };
-
+    ///
     ///     client.create_user(user).await?;
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn create_user<T: serde::Serialize>(&self, user: T) -> Result<User, reqwest::Error> {
-        let endpoint = format!("{}/admin/users", self.url);
+    pub async fn create_user<T: serde::Serialize>(&self, user: T) -> Result<User, ApiError> {
+        self.admin_create_user(user, None).await
+    }
 
-        let json = serde_json::to_value(&user).unwrap();
+    /// Creates a user, sending `idempotency_key` as `Idempotency-Key` header
+    ///
+    /// Retried like idempotent requests, see [`Api::sign_up_with_idempotency_key`].
+    pub async fn create_user_with_idempotency_key<T: serde::Serialize>(
+        &self,
+        user: T,
+        idempotency_key: &str,
+    ) -> Result<User, ApiError> {
+        self.admin_create_user(user, Some(idempotency_key)).await
+    }
+
+    async fn admin_create_user<T: serde::Serialize>(
+        &self,
+        user: T,
+        idempotency_key: Option<&str>,
+    ) -> Result<User, ApiError> {
+        let endpoint = format!("{}/admin/users", self.url);
 
-        let client = reqwest::Client::new();
-        let user: User = client
+        let request = self
+            .client
             .post(endpoint)
             .headers(self.headers.clone())
-            .json(&json)
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
+            .json(&user);
+        let response = self
+            .send_with_idempotency_key(request, idempotency_key)
             .await?;
 
-        return Ok(user);
+        parse_json(response).await
     }
 
     /// Updates a user by id
@@ -635,28 +2432,23 @@ impl Api {
     /// # Example
     ///
     /// ```
-    /// use go_true::{Api};
+    /// use go_true::{AdminUserAttributes, Api};
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ///     let url = "http://localhost:9998".to_string();
-    ///     let mut client = Api::new(url);
+    ///     let client = Api::new(url);
     ///
     ///     let user = AdminUserAttributes {
-    ///         email: "oldemail@example.com",
+    ///         email: Some(String::from("oldemail@example.com")),
     ///         password: Some(String::from("Abcd1234!")),
-    ///         data: None,
-    ///         email_confirmed: None,
-    ///         phone_confirmed: None,
+    ///         ..Default::default()
     ///     };
     ///
     ///     let create_response = client.create_user(user).await?;
     ///     let user = AdminUserAttributes {
-    ///         email: "newemail@example.com".to_string(),
-    ///         password: None,
-    ///         data: None,
-    ///         email_confirmed: None,
-    ///         phone_confirmed: None,
+    ///         email: Some(String::from("newemail@example.com")),
+    ///         ..Default::default()
     ///     };
     ///
     ///     let update_response = client
@@ -670,23 +2462,17 @@ impl Api {
         &self,
         id: &str,
         user: T,
-    ) -> Result<User, reqwest::Error> {
+    ) -> Result<User, ApiError> {
         let endpoint = format!("{}/admin/users/{}", self.url, id);
 
-        let json = serde_json::to_value(&user).unwrap();
-
-        let client = reqwest::Client::new();
-        let user: User = client
+        let request = self
+            .client
             .put(endpoint)
             .headers(self.headers.clone())
-            .json(&json)
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?;
+            .json(&user);
+        let response = self.send(request).await?;
 
-        return Ok(user);
+        parse_json(response).await
     }
 
     /// Deletes a user by id
@@ -694,37 +2480,468 @@ impl Api {
     /// # Example
     ///
     /// ```
-    /// use go_true::{Api};
+    /// use go_true::{AdminUserAttributes, Api};
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ///     let url = "http://localhost:9998".to_string();
-    ///     let mut client = Api::new(url);
+    ///     let client = Api::new(url);
     ///
     ///     let user = AdminUserAttributes {
-    ///         email: "delete@example.com",
+    ///         email: Some(String::from("delete@example.com")),
     ///         password: Some(String::from("Abcd1234!")),
-    ///         data: None,
-    ///         email_confirmed: None,
-    ///         phone_confirmed: None,
+    ///         ..Default::default()
     ///     };
-
+    ///
     ///     let user = client.create_user(user).await?;
     ///     client.delete_user(&user.id).await?;
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn delete_user(&self, user_id: &str) -> Result<bool, reqwest::Error> {
+    pub async fn delete_user(&self, user_id: &str) -> Result<(), ApiError> {
+        self.delete_user_with(user_id, false).await
+    }
+
+    /// Deletes a user, optionally keeping the row in the database
+    ///
+    /// A soft deleted user can no longer sign in, but its data is kept until it is purged.
+    /// Requires the service role key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let result = client.delete_user_with("user_id", true).await;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn delete_user_with(
+        &self,
+        user_id: &str,
+        should_soft_delete: bool,
+    ) -> Result<(), ApiError> {
         let endpoint = format!("{}/admin/users/{}", self.url, user_id);
 
-        self.client
+        let body = json!({ "should_soft_delete": should_soft_delete });
+
+        let request = self
+            .client
             .delete(endpoint)
             .headers(self.headers.clone())
-            .send()
-            .await?
-            .error_for_status()?;
+            .json(&body);
+        self.send(request).await?;
+
+        Ok(())
+    }
+
+    /// Gets the auth configuration of the server, e.g. which external providers are enabled
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let settings = client.get_settings().await;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_settings(&self) -> Result<Settings, ApiError> {
+        let endpoint = format!("{}/settings", self.url);
+
+        let request = self.client.get(endpoint).headers(self.headers.clone());
+        let response = self.send_idempotent(request).await?;
+
+        parse_json(response).await
+    }
+
+    /// Checks that the server is reachable and returns its name and version
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let url = "http://localhost:9998".to_string();
+    ///     let client = Api::new(url);
+    ///
+    ///     let health = client.health_check().await;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn health_check(&self) -> Result<HealthStatus, ApiError> {
+        let endpoint = format!("{}/health", self.url);
+
+        let request = self.client.get(endpoint).headers(self.headers.clone());
+        let response = self.send_idempotent(request).await?;
+
+        parse_json(response).await
+    }
+
+    /// Verifies the signature and expiry of an access token with the JWT secret of the server
+    /// and returns its claims
+    ///
+    /// Only HS256 signed tokens issued for the `authenticated` audience are accepted. This lets a
+    /// backend authenticate requests without calling [`get_user`](Api::get_user) on every request.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// let result = Api::verify_jwt("access_token", "jwt_secret");
+    /// ```
+    #[cfg(feature = "verify")]
+    pub fn verify_jwt(token: &str, secret: &str) -> Result<Claims, Error> {
+        use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_audience(&["authenticated"]);
+
+        let key = DecodingKey::from_secret(secret.as_bytes());
+        let data = jsonwebtoken::decode::<Claims>(token, &key, &validation)
+            .map_err(|e| Error::InvalidJwt(e.to_string()))?;
+
+        Ok(data.claims)
+    }
+
+    /// Creates a request with the configured headers, authorized with `access_token` as bearer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `access_token` isn't a valid header value.
+    fn authorized_request(
+        &self,
+        method: Method,
+        endpoint: String,
+        access_token: &str,
+    ) -> RequestBuilder {
+        let mut headers: HeaderMap = self.headers.clone();
+        let bearer = format!("Bearer {access_token}");
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(bearer.as_ref()).expect("Invalid header value."),
+        );
+
+        self.client.request(method, endpoint).headers(headers)
+    }
+
+    /// Sends a mutating request, retrying it like an idempotent request if an `idempotency_key`
+    /// is given.
+    async fn send_with_idempotency_key(
+        &self,
+        request: RequestBuilder,
+        idempotency_key: Option<&str>,
+    ) -> Result<Response, ApiError> {
+        match idempotency_key {
+            Some(idempotency_key) => {
+                let request = request.header(IDEMPOTENCY_KEY, idempotency_key);
+                self.send_idempotent(request).await
+            }
+            None => self.send(request).await,
+        }
+    }
+
+    /// Sends a request and turns any non-success status into an [`ApiError`].
+    pub(crate) async fn send(&self, request: RequestBuilder) -> Result<Response, ApiError> {
+        let mut request = request.build()?;
+        let has_body = request.body().is_some();
+        set_json_headers(request.headers_mut(), has_body);
+
+        if self.dry_run {
+            return Err(ApiError::DryRun(Box::new(request)));
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let hook = self
+            .on_response
+            .as_ref()
+            .map(|hook| (hook, request.url().path().to_string(), Instant::now()));
+
+        let response = self.execute(request).await?;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some((hook, path, started)) = hook {
+            hook.call(&path, response.status(), started.elapsed());
+        }
+
+        if response.status().is_client_error() || response.status().is_server_error() {
+            return Err(error_from_response(response).await);
+        }
+
+        Ok(response)
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    async fn execute(&self, request: Request) -> Result<Response, reqwest::Error> {
+        self.client.execute(request).await
+    }
+
+    /// Sends a request inside a `gotrue.request` span recording method, path, status and
+    /// duration. Query strings, headers and bodies are left out as they may contain secrets.
+    #[cfg(feature = "tracing")]
+    async fn execute(&self, request: Request) -> Result<Response, reqwest::Error> {
+        use tracing::{field, Instrument};
+
+        let span = tracing::info_span!(
+            "gotrue.request",
+            http.method = %request.method(),
+            url.path = request.url().path(),
+            http.status_code = field::Empty,
+            duration_ms = field::Empty,
+        );
+        #[cfg(not(target_arch = "wasm32"))]
+        let started = Instant::now();
+
+        let result = self.client.execute(request).instrument(span.clone()).await;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        span.record("duration_ms", started.elapsed().as_millis() as u64);
+        match &result {
+            Ok(response) => {
+                span.record("http.status_code", response.status().as_u16());
+                tracing::debug!(parent: &span, "GoTrue request finished");
+            }
+            Err(e) => tracing::warn!(
+                parent: &span,
+                timeout = e.is_timeout(),
+                "GoTrue request failed"
+            ),
+        }
+
+        result
+    }
+
+    /// Like [`Api::send`], but retries transient failures if a [`RetryConfig`] is set.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) async fn send_idempotent(
+        &self,
+        mut request: RequestBuilder,
+    ) -> Result<Response, ApiError> {
+        let retry = match &self.retry {
+            Some(retry) => retry,
+            None => return self.send(request).await,
+        };
+
+        let started = Instant::now();
+        let mut attempt = 0;
+
+        loop {
+            let next = request.try_clone();
+            let result = self.send(request).await;
+
+            request = match (&result, next) {
+                (Err(e), Some(next)) if retry::is_retryable(e) && attempt < retry.max_retries => {
+                    next
+                }
+                _ => return result,
+            };
+
+            let delay = match &result {
+                Err(ApiError::RateLimited {
+                    retry_after: Some(retry_after),
+                }) => retry.delay(attempt).max(*retry_after),
+                _ => retry.delay(attempt),
+            };
+            if started.elapsed() + delay > retry.deadline {
+                return result;
+            }
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Retries are not supported on WASM, so this is the same as [`Api::send`].
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) async fn send_idempotent(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<Response, ApiError> {
+        self.send(request).await
+    }
+}
+
+/// Reads an environment variable, treating empty and non UTF-8 values as unset.
+pub(crate) fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Reads and validates `GOTRUE_URL`.
+pub(crate) fn url_from_env() -> Result<String, Error> {
+    let url =
+        env_var(URL_VAR).ok_or_else(|| Error::InvalidConfig(format!("{URL_VAR} is not set")))?;
+
+    match url::Url::parse(&url) {
+        Ok(_) => Ok(url),
+        Err(e) => Err(Error::InvalidConfig(format!(
+            "{URL_VAR} is not a valid url: {e}"
+        ))),
+    }
+}
+
+/// Removes trailing slashes so endpoints can be appended as `{url}/path`.
+pub(crate) fn normalize_url(url: String) -> String {
+    if let Err(e) = url::Url::parse(&url) {
+        panic!("Invalid GoTrue url {url:?}: {e}");
+    }
+
+    url.trim_end_matches('/').to_string()
+}
+
+/// Client builder sending [`DEFAULT_USER_AGENT`] unless a request sets its own `User-Agent`.
+pub(crate) fn client_builder() -> reqwest::ClientBuilder {
+    let mut headers = HeaderMap::new();
+    headers.insert(USER_AGENT, HeaderValue::from_static(DEFAULT_USER_AGENT));
+
+    let client = reqwest::Client::builder().default_headers(headers);
+    #[cfg(all(feature = "rustls-tls", not(target_arch = "wasm32")))]
+    let client = client.use_rustls_tls();
+
+    client
+}
+
+/// Adds `captcha_token` to a request body in the `gotrue_meta_security` object GoTrue verifies
+/// against hCaptcha or Turnstile.
+fn with_captcha_token(
+    mut body: serde_json::Value,
+    captcha_token: Option<&str>,
+) -> serde_json::Value {
+    if let Some(captcha_token) = captcha_token {
+        body["gotrue_meta_security"] = json!({ "captcha_token": captcha_token });
+    }
+
+    body
+}
+
+/// Sets `Accept` and, for requests with a body, `Content-Type` to JSON unless already set, some
+/// gateways in front of GoTrue reject requests without them or answer with HTML error pages.
+pub(crate) fn set_json_headers(headers: &mut HeaderMap, has_body: bool) {
+    let json = HeaderValue::from_static("application/json");
+
+    headers.entry(ACCEPT).or_insert_with(|| json.clone());
+    if has_body {
+        headers.entry(CONTENT_TYPE).or_insert(json);
+    }
+}
+
+pub(crate) async fn parse_json<T: DeserializeOwned>(response: Response) -> Result<T, ApiError> {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.bytes().await?;
+
+    parse_body(status, &headers, &body)
+}
+
+/// Deserializes a response body, reporting bodies that are declared as something other than JSON
+/// as [`ApiError::UnexpectedResponse`] instead of a confusing parse error.
+pub(crate) fn parse_body<T: DeserializeOwned>(
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<T, ApiError> {
+    if !is_json(headers) {
+        return Err(unexpected_response(status, body));
+    }
+
+    Ok(serde_json::from_slice(body)?)
+}
+
+/// Returns `false` if the `Content-Type` header names anything but JSON. A missing header is
+/// accepted since GoTrue doesn't set it on every response.
+fn is_json(headers: &HeaderMap) -> bool {
+    match headers.get(CONTENT_TYPE).map(HeaderValue::to_str) {
+        Some(Ok(content_type)) => content_type.to_ascii_lowercase().contains("json"),
+        Some(Err(_)) => false,
+        None => true,
+    }
+}
+
+fn unexpected_response(status: StatusCode, body: &[u8]) -> ApiError {
+    const SNIPPET_LENGTH: usize = 200;
+
+    let body = String::from_utf8_lossy(body);
+
+    ApiError::UnexpectedResponse {
+        status: status.as_u16(),
+        body_snippet: body.trim().chars().take(SNIPPET_LENGTH).collect(),
+    }
+}
+
+async fn parse_paginated_users(response: Response) -> Result<PaginatedUsers, ApiError> {
+    let headers = response.headers().clone();
+    let body: UserList = parse_json(response).await?;
+
+    Ok(PaginatedUsers::new(body.users, &headers))
+}
+
+async fn error_from_response(response: Response) -> ApiError {
+    let status = response.status();
+    let headers = response.headers().clone();
+
+    match response.bytes().await {
+        Ok(body) => error_from_body(status, &headers, &body),
+        Err(e) => ApiError::Transport(e),
+    }
+}
+
+/// Parses a `Retry-After` header given in seconds. HTTP dates are not supported by GoTrue.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let seconds = headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+
+    Some(Duration::from_secs(seconds))
+}
+
+pub(crate) fn error_from_body(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> ApiError {
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return ApiError::RateLimited {
+            retry_after: retry_after(headers),
+        };
+    }
+
+    if !body.is_empty() && !is_json(headers) {
+        return unexpected_response(status, body);
+    }
+
+    let body: GoTrueErrorBody = serde_json::from_slice(body).unwrap_or_default();
+    let message = match body.message() {
+        message if message.is_empty() => status.canonical_reason().unwrap_or_default().to_string(),
+        message => message,
+    };
+
+    if let Some(weak_password) = body.weak_password {
+        return ApiError::WeakPassword {
+            status: status.as_u16(),
+            message,
+            reasons: weak_password.reasons,
+        };
+    }
 
-        return Ok(true);
+    ApiError::GoTrue {
+        status: status.as_u16(),
+        code: body.code(),
+        message,
     }
 }
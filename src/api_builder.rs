@@ -0,0 +1,204 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderValue, IntoHeaderName, USER_AGENT};
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::StatusCode;
+
+use crate::api::{client_builder, Api};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{response_hook::ResponseHook, retry::RetryConfig};
+
+/// Builder for [`Api`], created by [`Api::builder`].
+#[derive(Debug, Clone, Default)]
+pub struct ApiBuilder {
+    url: Option<String>,
+    headers: HeaderMap,
+    redirect_to: Option<String>,
+    dry_run: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    timeout: Option<Duration>,
+    #[cfg(not(target_arch = "wasm32"))]
+    connect_timeout: Option<Duration>,
+    #[cfg(not(target_arch = "wasm32"))]
+    retry: Option<RetryConfig>,
+    #[cfg(not(target_arch = "wasm32"))]
+    proxies: Vec<reqwest::Proxy>,
+    #[cfg(not(target_arch = "wasm32"))]
+    on_response: Option<ResponseHook>,
+}
+
+impl ApiBuilder {
+    pub fn new() -> ApiBuilder {
+        ApiBuilder::default()
+    }
+
+    /// Sets the url of the GoTrue server.
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Adds a header sent with every request, replacing a previous value of the same header.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `header_value` isn't a valid header value.
+    pub fn header(
+        mut self,
+        header_name: impl IntoHeaderName,
+        header_value: impl AsRef<str>,
+    ) -> Self {
+        self.headers.insert(
+            header_name,
+            HeaderValue::from_str(header_value.as_ref()).expect("Invalid header value."),
+        );
+        self
+    }
+
+    /// Adds all given headers, replacing previous values of the same headers.
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request instead of `go-true-rs/<version>`.
+    pub fn user_agent(self, user_agent: impl AsRef<str>) -> Self {
+        self.header(USER_AGENT, user_agent)
+    }
+
+    /// Sets the default `redirect_to`, see [`Api::with_redirect_to`].
+    pub fn redirect_to(mut self, redirect_to: impl Into<String>) -> Self {
+        self.redirect_to = Some(redirect_to.into());
+        self
+    }
+
+    /// Builds requests without sending them, see [`Api::with_dry_run`].
+    pub fn dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Makes requests fail after `timeout`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Makes connection attempts fail after `connect_timeout`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Sends requests through `proxy`. Can be called multiple times, e.g. for separate HTTP and
+    /// HTTPS proxies.
+    ///
+    /// Without a proxy the `HTTP_PROXY` and `HTTPS_PROXY` environment variables are used. When
+    /// passing your own client to [`Api::new_with_client`], configure the proxy on that client
+    /// instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// let client = Api::builder()
+    ///     .url("http://your.gotrue.endpoint")
+    ///     .proxy(reqwest::Proxy::all("http://proxy.internal:3128").unwrap())
+    ///     .build();
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Retries idempotent requests on transient failures, see [`Api::with_retry`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Calls `hook` with the path, status and duration of every request GoTrue answered, e.g. to
+    /// record metrics. Requests that fail without a response, like timeouts, are not reported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Api;
+    ///
+    /// let client = Api::builder()
+    ///     .url("http://your.gotrue.endpoint")
+    ///     .on_response(|path, status, duration| {
+    ///         println!("{path} answered {status} in {}ms", duration.as_millis());
+    ///     })
+    ///     .build();
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn on_response(
+        mut self,
+        hook: impl Fn(&str, StatusCode, Duration) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_response = Some(ResponseHook::new(hook));
+        self
+    }
+
+    /// Builds the [`Api`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if no valid url was set or the HTTP client can't be built.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, RetryConfig};
+    /// use std::time::Duration;
+    ///
+    /// let client = Api::builder()
+    ///     .url("http://your.gotrue.endpoint")
+    ///     .header("apikey", "super.secret.key")
+    ///     .user_agent("my-app/1.0")
+    ///     .timeout(Duration::from_secs(10))
+    ///     .retry(RetryConfig::default())
+    ///     .build();
+    /// ```
+    pub fn build(self) -> Api {
+        let url = self.url.expect("ApiBuilder requires a url.");
+
+        #[allow(unused_mut)]
+        let mut client = client_builder();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(timeout) = self.timeout {
+                client = client.timeout(timeout);
+            }
+            if let Some(connect_timeout) = self.connect_timeout {
+                client = client.connect_timeout(connect_timeout);
+            }
+            for proxy in self.proxies {
+                client = client.proxy(proxy);
+            }
+        }
+
+        let client = client.build().expect("Failed to build HTTP client.");
+
+        let mut api = Api::new_with_client(url, client);
+        api.headers = self.headers;
+        api.redirect_to = self.redirect_to;
+        api.dry_run = self.dry_run;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            api.retry = self.retry;
+            api.on_response = self.on_response;
+        }
+
+        api
+    }
+}
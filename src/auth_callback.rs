@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use url::{form_urlencoded, Url};
+
+use crate::error::Error;
+
+/// Tokens GoTrue appends to the redirect url after an OAuth sign in or a followed email link,
+/// see [`parse_auth_callback`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct AuthCallback {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub token_type: String,
+    pub expires_in: i32,
+    /// Expiry of the access token as a unix timestamp in seconds.
+    pub expires_at: Option<i64>,
+    /// Kind of the followed link, e.g. `signup`, `magiclink`, `recovery` or `invite`. `None`
+    /// after an OAuth sign in.
+    pub callback_type: Option<String>,
+    /// Access token of the OAuth provider, e.g. to call the GitHub API on behalf of the user.
+    pub provider_token: Option<String>,
+    pub provider_refresh_token: Option<String>,
+}
+
+/// Parses the tokens out of the url GoTrue redirected to after an OAuth sign in, a magic link or
+/// an email confirmation.
+///
+/// The tokens are read from the fragment, where GoTrue puts them, or otherwise the query.
+/// Errors GoTrue reports in the url, e.g. an expired link, are returned as
+/// [`Error::AuthCallbackFailed`]. With the PKCE flow the url only contains a `code`, exchange it
+/// with [`Api::exchange_code_for_session`](crate::Api::exchange_code_for_session) instead.
+///
+/// # Example
+///
+/// ```
+/// use go_true::parse_auth_callback;
+///
+/// let url = "https://example.com/welcome#access_token=access-token&expires_in=3600\
+///     &refresh_token=refresh-token&token_type=bearer&type=signup";
+/// let callback = parse_auth_callback(url).unwrap();
+///
+/// assert_eq!(callback.refresh_token, "refresh-token");
+/// assert_eq!(callback.callback_type.as_deref(), Some("signup"));
+/// ```
+pub fn parse_auth_callback(url: &str) -> Result<AuthCallback, Error> {
+    let url = Url::parse(url).map_err(|e| Error::InvalidAuthCallback(e.to_string()))?;
+
+    let mut params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    if let Some(fragment) = url.fragment() {
+        params.extend(form_urlencoded::parse(fragment.as_bytes()).into_owned());
+    }
+
+    if params.contains_key("error") || params.contains_key("error_description") {
+        let code = params
+            .get("error_code")
+            .or_else(|| params.get("error"))
+            .cloned()
+            .unwrap_or_default();
+        let message = params
+            .get("error_description")
+            .or_else(|| params.get("error"))
+            .cloned()
+            .unwrap_or_default();
+
+        return Err(Error::AuthCallbackFailed { code, message });
+    }
+
+    let mut take = |key: &str| params.remove(key).filter(|value| !value.is_empty());
+
+    let (access_token, refresh_token) = match (take("access_token"), take("refresh_token")) {
+        (Some(access_token), Some(refresh_token)) => (access_token, refresh_token),
+        _ if take("code").is_some() => {
+            return Err(Error::InvalidAuthCallback(
+                "contains a PKCE auth code instead of tokens".to_string(),
+            ))
+        }
+        _ => {
+            return Err(Error::InvalidAuthCallback(
+                "missing access_token or refresh_token".to_string(),
+            ))
+        }
+    };
+
+    let expires_in = take("expires_in")
+        .and_then(|expires_in| expires_in.parse().ok())
+        .ok_or_else(|| Error::InvalidAuthCallback("missing or invalid expires_in".to_string()))?;
+
+    Ok(AuthCallback {
+        access_token,
+        refresh_token,
+        token_type: take("token_type").unwrap_or_else(|| String::from("bearer")),
+        expires_in,
+        expires_at: take("expires_at").and_then(|expires_at| expires_at.parse().ok()),
+        callback_type: take("type"),
+        provider_token: take("provider_token"),
+        provider_refresh_token: take("provider_refresh_token"),
+    })
+}
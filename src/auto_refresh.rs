@@ -0,0 +1,126 @@
+use std::{
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+use tokio::{
+    sync::{broadcast, watch, Mutex},
+    task::JoinHandle,
+};
+
+use crate::{api::Api, events::AuthChangeEvent, session::Session, session_store::SessionStore};
+
+/// Delay before retrying after a failed refresh.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Handle to the background task started by [`Client::start_auto_refresh`](crate::Client::start_auto_refresh).
+///
+/// The task is aborted when the handle is dropped.
+#[derive(Debug)]
+pub struct AutoRefreshHandle {
+    task: JoinHandle<()>,
+}
+
+impl AutoRefreshHandle {
+    pub(crate) fn new(task: JoinHandle<()>) -> AutoRefreshHandle {
+        AutoRefreshHandle { task }
+    }
+
+    /// Stops the background task.
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+}
+
+impl Drop for AutoRefreshHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+pub(crate) async fn run(
+    api: Arc<Api>,
+    sender: Weak<watch::Sender<Option<Session>>>,
+    mut receiver: watch::Receiver<Option<Session>>,
+    session_store: Option<Arc<dyn SessionStore>>,
+    events: broadcast::Sender<AuthChangeEvent>,
+    refresh_lock: Arc<Mutex<()>>,
+    refresh_before: Duration,
+) {
+    loop {
+        let current = receiver.borrow_and_update().clone();
+        let session = match current {
+            Some(session) => session,
+            None => {
+                // Wait for a session to be set. Fails once the client has been dropped.
+                if receiver.changed().await.is_err() {
+                    return;
+                }
+                continue;
+            }
+        };
+
+        let wait = session
+            .time_until_expiry()
+            .map(|remaining| remaining.saturating_sub(refresh_before))
+            .unwrap_or_default();
+
+        tokio::select! {
+            changed = receiver.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+                continue;
+            }
+            _ = tokio::time::sleep(wait) => {}
+        }
+
+        let guard = refresh_lock.lock().await;
+
+        // The client may have refreshed the session while we waited for the lock.
+        if receiver.has_changed().unwrap_or(false) {
+            continue;
+        }
+
+        let result = api.refresh_access_token(&session.refresh_token).await;
+
+        let sender = match sender.upgrade() {
+            Some(sender) => sender,
+            None => return,
+        };
+
+        match result {
+            Ok(refreshed) => {
+                // Only replace the session we refreshed, it may have been swapped out meanwhile.
+                let replaced = sender.send_if_modified(|current| match current {
+                    Some(current) if current.refresh_token == session.refresh_token => {
+                        *current = refreshed.clone();
+                        true
+                    }
+                    _ => false,
+                });
+                drop(sender);
+
+                if replaced {
+                    if let Some(store) = &session_store {
+                        store.save(&refreshed).await;
+                    }
+                    let _ = events.send(AuthChangeEvent::TokenRefreshed(refreshed));
+                }
+            }
+            Err(_) => {
+                drop(sender);
+                drop(guard);
+
+                tokio::select! {
+                    changed = receiver.changed() => {
+                        if changed.is_err() {
+                            return;
+                        }
+                    }
+                    _ = tokio::time::sleep(RETRY_DELAY) => {}
+                }
+            }
+        }
+    }
+}
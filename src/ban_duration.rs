@@ -0,0 +1,42 @@
+use std::{fmt, time::Duration};
+
+use serde::{Serialize, Serializer};
+
+/// Duration a user is banned for, sent to GoTrue as a duration string like `24h` or `none`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BanDuration {
+    /// Bans the user for the given duration, counted from now.
+    For(Duration),
+    /// Lifts an existing ban.
+    None,
+}
+
+impl fmt::Display for BanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let duration = match self {
+            BanDuration::For(duration) => duration,
+            BanDuration::None => return write!(f, "none"),
+        };
+
+        let seconds = duration.as_secs();
+        let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+        if hours > 0 {
+            write!(f, "{hours}h")?;
+        }
+        if minutes > 0 {
+            write!(f, "{minutes}m")?;
+        }
+        if seconds > 0 || (hours == 0 && minutes == 0) {
+            write!(f, "{seconds}s")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Serialize for BanDuration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
@@ -0,0 +1,543 @@
+//! A blocking GoTrue API client.
+//!
+//! [`Api`] mirrors the auth flows of the async [`crate::Api`] with the same method names, but
+//! uses [`reqwest::blocking::Client`] so it can be called from a synchronous `main`.
+//! Admin methods are only available on the async client.
+//!
+//! It must not be used from within an async runtime.
+//!
+//! This doesn't remove tokio from the dependency tree: the async clients are always compiled and
+//! [`reqwest::blocking`] runs a tokio runtime on a background thread itself. Only your own code
+//! doesn't need to set up a runtime.
+
+use std::fmt;
+
+use reqwest::{
+    blocking::{RequestBuilder, Response},
+    header::{HeaderMap, HeaderValue, IntoHeaderName, InvalidHeaderValue, AUTHORIZATION},
+    Method,
+};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::{
+    api::{
+        error_from_body, normalize_url, parse_body, set_json_headers, EmailOrPhone,
+        RedactedHeaders, DEFAULT_USER_AGENT,
+    },
+    error::ApiError,
+    grant_type::GrantType,
+    health::HealthStatus,
+    session::Session,
+    settings::Settings,
+    sign_up_result::SignUpResult,
+    user::User,
+    user_attributes::UserAttributes,
+    user_update::UserUpdate,
+};
+
+#[derive(Clone)]
+pub struct Api {
+    url: String,
+    headers: HeaderMap,
+    client: reqwest::blocking::Client,
+}
+
+impl fmt::Debug for Api {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Api")
+            .field("url", &self.url)
+            .field("headers", &RedactedHeaders(&self.headers))
+            .finish_non_exhaustive()
+    }
+}
+
+impl Api {
+    /// Creates a blocking GoTrue API client.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::blocking::Api;
+    ///
+    /// let client = Api::new("http://your.gotrue.endpoint".to_string());
+    /// ```
+    pub fn new(url: String) -> Api {
+        Api {
+            url: normalize_url(url),
+            headers: HeaderMap::new(),
+            client: client_builder()
+                .build()
+                .expect("Failed to build HTTP client."),
+        }
+    }
+
+    pub fn new_with_client(url: String, client: reqwest::blocking::Client) -> Api {
+        Api {
+            url: normalize_url(url),
+            headers: HeaderMap::new(),
+            client,
+        }
+    }
+
+    /// Add arguments to the header
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::blocking::Api;
+    ///
+    /// let client = Api::new("https://your.gotrue.endpoint".to_string())
+    ///     .insert_header("apikey", "super.secret.key");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `header_value` isn't a valid header value, e.g. because it contains a newline.
+    /// Use [`Api::try_insert_header`] for values that aren't known to be valid.
+    pub fn insert_header(
+        self,
+        header_name: impl IntoHeaderName,
+        header_value: impl AsRef<str>,
+    ) -> Self {
+        self.try_insert_header(header_name, header_value)
+            .expect("Invalid header value.")
+    }
+
+    /// Add arbitrary headers to the request, returning an error if `header_value` isn't a
+    /// valid header value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::blocking::Api;
+    ///
+    /// let api_key = std::env::var("GOTRUE_API_KEY").unwrap_or_default();
+    /// let client = Api::new("https://your.gotrue.endpoint".to_string())
+    ///     .try_insert_header("apikey", api_key);
+    /// ```
+    pub fn try_insert_header(
+        mut self,
+        header_name: impl IntoHeaderName,
+        header_value: impl AsRef<str>,
+    ) -> Result<Self, InvalidHeaderValue> {
+        self.headers
+            .insert(header_name, HeaderValue::from_str(header_value.as_ref())?);
+        Ok(self)
+    }
+
+    /// Returns the url of the GoTrue server without trailing slash.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the headers sent with every request, like `apikey`.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Signs up for a new account
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{blocking::Api, EmailOrPhone};
+    ///
+    /// let client = Api::new("http://localhost:9998".to_string());
+    ///
+    /// let email = "email@example.com".to_string();
+    /// let password = "Abcd1234!".to_string();
+    ///
+    /// let result = client.sign_up(EmailOrPhone::Email(email), &password);
+    /// ```
+    pub fn sign_up(
+        &self,
+        email_or_phone: EmailOrPhone,
+        password: &String,
+    ) -> Result<SignUpResult, ApiError> {
+        let endpoint = format!("{}/signup", self.url);
+
+        let body = match email_or_phone {
+            EmailOrPhone::Email(email) => json!({
+                "email": email,
+                "password": &password,
+            }),
+            EmailOrPhone::Phone(phone) => json!({
+                "phone": phone,
+                "password": &password
+            }),
+        };
+
+        let request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        let response = self.send(request)?;
+
+        parse_json(response)
+    }
+
+    /// Signs in as a new anonymous user, optionally attaching user metadata
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::blocking::Api;
+    ///
+    /// let client = Api::new("http://localhost:9998".to_string());
+    ///
+    /// let result = client.sign_in_anonymously(None);
+    /// ```
+    pub fn sign_in_anonymously(
+        &self,
+        data: Option<serde_json::Value>,
+    ) -> Result<Session, ApiError> {
+        let endpoint = format!("{}/signup", self.url);
+
+        let body = json!({
+            "data": data.unwrap_or_else(|| json!({})),
+        });
+
+        let request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        let response = self.send(request)?;
+
+        parse_json(response)
+    }
+
+    /// Signs into an existing account
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{blocking::Api, EmailOrPhone};
+    ///
+    /// let client = Api::new("http://localhost:9998".to_string());
+    ///
+    /// let email = "email@example.com".to_string();
+    /// let password = "Abcd1234!".to_string();
+    ///
+    /// let result = client.sign_in(EmailOrPhone::Email(email), &password);
+    /// ```
+    pub fn sign_in(
+        &self,
+        email_or_phone: EmailOrPhone,
+        password: &String,
+    ) -> Result<Session, ApiError> {
+        let endpoint = format!("{}/token?grant_type={}", self.url, GrantType::Password);
+
+        let body = match email_or_phone {
+            EmailOrPhone::Email(email) => json!({
+                "email": email,
+                "password": &password,
+            }),
+            EmailOrPhone::Phone(phone) => json!({
+                "phone": phone,
+                "password": &password
+            }),
+        };
+
+        let request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        let response = self.send(request)?;
+
+        parse_json(response)
+    }
+
+    /// Sends an OTP Code and creates user if it does not exist
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{blocking::Api, EmailOrPhone};
+    ///
+    /// let client = Api::new("http://localhost:9998".to_string());
+    ///
+    /// let email = "email@example.com".to_string();
+    ///
+    /// let result = client.send_otp(EmailOrPhone::Email(email), None);
+    /// ```
+    pub fn send_otp(
+        &self,
+        email_or_phone: EmailOrPhone,
+        should_create_user: Option<bool>,
+    ) -> Result<bool, ApiError> {
+        let endpoint = format!("{}/otp", self.url);
+
+        let body = match email_or_phone {
+            EmailOrPhone::Email(email) => json!({
+                "email": email,
+                "should_create_user": Some(should_create_user)
+            }),
+            EmailOrPhone::Phone(phone) => json!({
+                "phone": phone,
+                "should_create_user": Some(should_create_user)
+            }),
+        };
+
+        let request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        self.send(request)?;
+
+        Ok(true)
+    }
+
+    /// Verifies a one time password and returns the created session
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{blocking::Api, OtpType, VerifyOtpParams};
+    ///
+    /// let client = Api::new("http://localhost:9998".to_string());
+    ///
+    /// let params = VerifyOtpParams::Email {
+    ///     email: "email@example.com".to_string(),
+    ///     token: "123456".to_string(),
+    ///     otp_type: OtpType::Email,
+    /// };
+    ///
+    /// let result = client.verify_otp(params);
+    /// ```
+    pub fn verify_otp<T: serde::Serialize>(&self, params: T) -> Result<Session, ApiError> {
+        let endpoint = format!("{}/verify", self.url);
+
+        let body = serde_json::to_value(&params)?;
+
+        let request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        let response = self.send(request)?;
+
+        parse_json(response)
+    }
+
+    /// Signs the current user out
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::blocking::Api;
+    ///
+    /// let client = Api::new("http://localhost:9998".to_string());
+    ///
+    /// let result = client.sign_out("access_token");
+    /// ```
+    pub fn sign_out(&self, access_token: &str) -> Result<bool, ApiError> {
+        let endpoint = format!("{}/logout", self.url);
+
+        let request = self.authorized_request(Method::POST, endpoint, access_token);
+        self.send(request)?;
+
+        Ok(true)
+    }
+
+    /// Sends password recovery email
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::blocking::Api;
+    ///
+    /// let client = Api::new("http://localhost:9998".to_string());
+    ///
+    /// let result = client.reset_password_for_email("random@mail.com");
+    /// ```
+    pub fn reset_password_for_email(&self, email: &str) -> Result<bool, ApiError> {
+        let endpoint = format!("{}/recover", self.url);
+
+        let body = json!({
+            "email": &email,
+        });
+
+        let request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        self.send(request)?;
+
+        Ok(true)
+    }
+
+    /// Refreshes the current session by refresh token
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::blocking::Api;
+    ///
+    /// let client = Api::new("http://localhost:9998".to_string());
+    ///
+    /// let result = client.refresh_access_token("refresh_token");
+    /// ```
+    pub fn refresh_access_token(&self, refresh_token: &str) -> Result<Session, ApiError> {
+        let endpoint = format!("{}/token?grant_type={}", self.url, GrantType::RefreshToken);
+        let body = json!({ "refresh_token": refresh_token });
+
+        let request = self
+            .client
+            .post(endpoint)
+            .headers(self.headers.clone())
+            .json(&body);
+        let response = self.send(request)?;
+
+        parse_json(response)
+    }
+
+    /// Gets a user by access token
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::blocking::Api;
+    ///
+    /// let client = Api::new("http://localhost:9998".to_string());
+    ///
+    /// let result = client.get_user("access_token");
+    /// ```
+    pub fn get_user(&self, jwt: &str) -> Result<User, ApiError> {
+        let endpoint = format!("{}/user", self.url);
+
+        let request = self.authorized_request(Method::GET, endpoint, jwt);
+        let response = self.send(request)?;
+
+        parse_json(response)
+    }
+
+    /// Updates a user
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{blocking::Api, UserAttributes};
+    /// use serde_json::json;
+    ///
+    /// let client = Api::new("http://localhost:9998".to_string());
+    ///
+    /// let attributes = UserAttributes {
+    ///     data: Some(json!({ "test": "test" })),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let result = client.update_user(attributes, "access_token");
+    /// ```
+    pub fn update_user(&self, user: UserAttributes, jwt: &str) -> Result<UserUpdate, ApiError> {
+        let endpoint = format!("{}/user", self.url);
+
+        let request = self
+            .authorized_request(Method::PUT, endpoint, jwt)
+            .json(&user);
+        let response = self.send(request)?;
+
+        parse_json(response)
+    }
+
+    /// Gets the auth configuration of the server
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::blocking::Api;
+    ///
+    /// let client = Api::new("http://localhost:9998".to_string());
+    ///
+    /// let settings = client.get_settings();
+    /// ```
+    pub fn get_settings(&self) -> Result<Settings, ApiError> {
+        let endpoint = format!("{}/settings", self.url);
+
+        let request = self.client.get(endpoint).headers(self.headers.clone());
+        let response = self.send(request)?;
+
+        parse_json(response)
+    }
+
+    /// Checks that the server is reachable and returns its name and version
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::blocking::Api;
+    ///
+    /// let client = Api::new("http://localhost:9998".to_string());
+    ///
+    /// let health = client.health_check();
+    /// ```
+    pub fn health_check(&self) -> Result<HealthStatus, ApiError> {
+        let endpoint = format!("{}/health", self.url);
+
+        let request = self.client.get(endpoint).headers(self.headers.clone());
+        let response = self.send(request)?;
+
+        parse_json(response)
+    }
+
+    /// Creates a request with the configured headers, authorized with `access_token` as bearer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `access_token` isn't a valid header value.
+    fn authorized_request(
+        &self,
+        method: Method,
+        endpoint: String,
+        access_token: &str,
+    ) -> RequestBuilder {
+        let mut headers: HeaderMap = self.headers.clone();
+        let bearer = format!("Bearer {access_token}");
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(bearer.as_ref()).expect("Invalid header value."),
+        );
+
+        self.client.request(method, endpoint).headers(headers)
+    }
+
+    /// Sends a request and turns any non-success status into an [`ApiError`].
+    fn send(&self, request: RequestBuilder) -> Result<Response, ApiError> {
+        let mut request = request.build()?;
+        let has_body = request.body().is_some();
+        set_json_headers(request.headers_mut(), has_body);
+
+        let response = self.client.execute(request)?;
+        let status = response.status();
+
+        if status.is_client_error() || status.is_server_error() {
+            let headers = response.headers().clone();
+            let body = response.bytes()?;
+            return Err(error_from_body(status, &headers, &body));
+        }
+
+        Ok(response)
+    }
+}
+
+/// Client builder with the default `User-Agent` and the TLS backend selected by features.
+fn client_builder() -> reqwest::blocking::ClientBuilder {
+    let client = reqwest::blocking::Client::builder().user_agent(DEFAULT_USER_AGENT);
+    #[cfg(feature = "rustls-tls")]
+    let client = client.use_rustls_tls();
+
+    client
+}
+
+fn parse_json<T: DeserializeOwned>(response: Response) -> Result<T, ApiError> {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.bytes()?;
+
+    parse_body(status, &headers, &body)
+}
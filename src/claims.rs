@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::Error;
+
+/// Claims of a GoTrue access token.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct Claims {
+    /// Id of the user the token was issued for.
+    pub sub: String,
+    /// Expiry as a unix timestamp in seconds.
+    pub exp: i64,
+    pub iat: Option<i64>,
+    pub aud: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub role: Option<String>,
+    /// Authenticator assurance level, `aal1` or `aal2` once MFA was verified.
+    pub aal: Option<String>,
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub is_anonymous: bool,
+    #[serde(default)]
+    pub app_metadata: Value,
+    #[serde(default)]
+    pub user_metadata: Value,
+}
+
+/// Decodes the payload of a JWT into [`Claims`].
+///
+/// The signature is **not** verified, so the claims must not be trusted for authorization.
+/// Use this on the client side to read the user id or expiry without calling the server.
+pub fn decode_jwt(token: &str) -> Result<Claims, Error> {
+    let mut parts = token.split('.');
+    let payload = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(_), Some(payload), Some(_), None) => payload,
+        _ => return Err(Error::InvalidJwt("expected three segments".to_string())),
+    };
+
+    let payload = base64::decode_config(payload, base64::URL_SAFE_NO_PAD)
+        .map_err(|e| Error::InvalidJwt(e.to_string()))?;
+
+    serde_json::from_slice(&payload).map_err(|e| Error::InvalidJwt(e.to_string()))
+}
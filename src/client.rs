@@ -1,19 +1,61 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+use std::{collections::HashMap, fmt, sync::Arc, time::UNIX_EPOCH};
+
+use tokio::sync::{broadcast, watch, Mutex};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::auto_refresh::{self, AutoRefreshHandle};
 use crate::{
     api::{Api, EmailOrPhone},
+    auth_callback::parse_auth_callback,
+    claims::decode_jwt,
     error::Error,
-    session::Session,
+    events::{AuthChangeEvent, EVENT_CAPACITY},
+    otp_params::OtpParams,
+    refresh_outcome::RefreshOutcome,
+    session::{self, Session},
+    session_store::SessionStore,
+    sign_out_scope::SignOutScope,
+    sign_up_options::SignUpOptions,
+    sign_up_result::SignUpResult,
+    user::User,
     user_attributes::UserAttributes,
     user_update::UserUpdate,
+    verify_otp_params::VerifyOtpParams,
 };
 
+/// Clones share the session, the auth state events and the refresh lock, so a signed in clone
+/// can be handed to other tasks.
+#[derive(Clone)]
 pub struct Client {
-    current_session: Option<Session>,
-    api: Api,
+    current_session: Arc<watch::Sender<Option<Session>>>,
+    api: Arc<Api>,
+    auto_refresh_token: bool,
+    session_store: Option<Arc<dyn SessionStore>>,
+    events: broadcast::Sender<AuthChangeEvent>,
+    /// Held while refreshing so concurrent callers don't spend the same refresh token twice.
+    refresh_lock: Arc<Mutex<()>>,
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("api", &self.api)
+            .field("authenticated", &self.is_authenticated())
+            .field("auto_refresh_token", &self.auto_refresh_token)
+            .field("session_store", &self.session_store.is_some())
+            .finish_non_exhaustive()
+    }
 }
 
 impl Client {
     /// Creates a GoTrue Client.
     ///
+    /// # Panics
+    ///
+    /// Panics if `url` is not a valid URL, see [`Api::new`].
+    ///
     /// # Example
     ///
     /// ```
@@ -23,19 +65,121 @@ impl Client {
     /// ```
     pub fn new(url: String) -> Client {
         Client {
-            current_session: None,
-            api: Api::new(url),
+            current_session: Arc::new(watch::channel(None).0),
+            api: Arc::new(Api::new(url)),
+            auto_refresh_token: true,
+            session_store: None,
+            events: broadcast::channel(EVENT_CAPACITY).0,
+            refresh_lock: Arc::new(Mutex::new(())),
         }
     }
 
     pub fn new_with_api(api: Api) -> Client {
         Client {
-            current_session: None,
-            api,
+            current_session: Arc::new(watch::channel(None).0),
+            api: Arc::new(api),
+            auto_refresh_token: true,
+            session_store: None,
+            events: broadcast::channel(EVENT_CAPACITY).0,
+            refresh_lock: Arc::new(Mutex::new(())),
         }
     }
 
-    /// Signs up a new user.
+    /// Creates a GoTrue Client that persists its session in `store`.
+    ///
+    /// A previously stored session is loaded right away, so the user stays signed in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Api, Client, FileSessionStore};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let api = Api::new("http://your.gotrue.endpoint".to_string());
+    ///
+    ///     let client = Client::with_session_store(api, FileSessionStore::new("session.json")).await;
+    /// }
+    /// ```
+    pub async fn with_session_store(api: Api, store: impl SessionStore + 'static) -> Client {
+        let session = store.load().await;
+
+        Client {
+            current_session: Arc::new(watch::channel(session).0),
+            api: Arc::new(api),
+            auto_refresh_token: true,
+            session_store: Some(Arc::new(store)),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+            refresh_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Enables or disables refreshing an expired session before authenticated requests.
+    /// Enabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Client;
+    ///
+    /// let client = Client::new("http://your.gotrue.endpoint".to_string())
+    ///     .auto_refresh_token(false);
+    /// ```
+    pub fn auto_refresh_token(mut self, auto_refresh_token: bool) -> Self {
+        self.auto_refresh_token = auto_refresh_token;
+        self
+    }
+
+    /// Returns the current session, if signed in.
+    ///
+    /// The session is returned by value since it may be replaced by the auto refresh task at any time.
+    pub fn current_session(&self) -> Option<Session> {
+        self.current_session.borrow().clone()
+    }
+
+    /// Returns the access token of the current session, if signed in.
+    pub fn access_token(&self) -> Option<String> {
+        self.current_session
+            .borrow()
+            .as_ref()
+            .map(|session| session.access_token.clone())
+    }
+
+    /// Returns true if the client holds a session.
+    pub fn is_authenticated(&self) -> bool {
+        self.current_session.borrow().is_some()
+    }
+
+    /// Subscribes to changes of the auth state, including refreshes done by the auto refresh task.
+    ///
+    /// Only events sent after subscribing are received. A receiver that falls behind misses the
+    /// oldest events.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{AuthChangeEvent, Client};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::new("http://your.gotrue.endpoint".to_string());
+    ///     let mut events = client.on_auth_state_change();
+    ///
+    ///     tokio::spawn(async move {
+    ///         while let Ok(event) = events.recv().await {
+    ///             if let AuthChangeEvent::SignedOut = event {
+    ///                 println!("signed out");
+    ///             }
+    ///         }
+    ///     });
+    /// }
+    /// ```
+    pub fn on_auth_state_change(&self) -> broadcast::Receiver<AuthChangeEvent> {
+        self.events.subscribe()
+    }
+
+    /// Signs up a new user. The user is only signed in if the server doesn't require
+    /// confirmation, see [`SignUpResult`].
     ///
     /// # Example
     ///
@@ -44,7 +188,7 @@ impl Client {
     ///
     /// #[tokio::main]
     ///     async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    ///     let client = Client::new("http://your.gotrue.endpoint".to_string());
+    ///     let mut client = Client::new("http://your.gotrue.endpoint".to_string());
     ///     let email = "some_email".to_string();
     ///     let password = "some_password".to_string();
     ///     let res = client
@@ -55,22 +199,77 @@ impl Client {
     pub async fn sign_up(
         &mut self,
         email_or_phone: EmailOrPhone,
-        password: &String,
-    ) -> Result<Session, Error> {
-        self.current_session = None;
-        let result = self.api.sign_up(email_or_phone, &password).await;
+        password: &str,
+    ) -> Result<SignUpResult, Error> {
+        self.signup(email_or_phone, password, SignUpOptions::default())
+            .await
+    }
+
+    /// Signs up a new user on a server with CAPTCHA protection enabled, see
+    /// [`Api::sign_up_with_captcha`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Client, EmailOrPhone};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = Client::new("http://your.gotrue.endpoint".to_string());
+    ///
+    ///     let email = "email@example.com".to_string();
+    ///     let result = client
+    ///         .sign_up_with_captcha(EmailOrPhone::Email(email), "Abcd1234!", "captcha-token")
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn sign_up_with_captcha(
+        &mut self,
+        email_or_phone: EmailOrPhone,
+        password: &str,
+        captcha_token: &str,
+    ) -> Result<SignUpResult, Error> {
+        let options = SignUpOptions {
+            captcha_token: Some(captcha_token.to_string()),
+            ..Default::default()
+        };
+
+        self.signup(email_or_phone, password, options).await
+    }
+
+    /// Signs up a new user with user metadata, a redirect url for the confirmation link or a
+    /// CAPTCHA token, see [`Api::sign_up_with`].
+    pub async fn sign_up_with(
+        &mut self,
+        email_or_phone: EmailOrPhone,
+        password: &str,
+        options: SignUpOptions,
+    ) -> Result<SignUpResult, Error> {
+        self.signup(email_or_phone, password, options).await
+    }
+
+    async fn signup(
+        &mut self,
+        email_or_phone: EmailOrPhone,
+        password: &str,
+        options: SignUpOptions,
+    ) -> Result<SignUpResult, Error> {
+        self.forget_session().await;
+        let result = self
+            .api
+            .signup(email_or_phone, password, options, None)
+            .await;
 
         match result {
-            Ok(session) => {
-                self.current_session = Some(session.clone());
-                return Ok(session);
-            }
-            Err(e) => {
-                if e.is_status() && e.status().unwrap().as_str() == "400" {
-                    return Err(Error::AlreadySignedUp);
+            Ok(result) => {
+                if let Some(session) = result.session() {
+                    self.set_current_session(Some(session.clone())).await;
+                    self.emit(AuthChangeEvent::SignedIn(session.clone()));
                 }
-                return Err(Error::InternalError);
+                Ok(result)
             }
+            Err(e) if e.status() == Some(400) => Err(Error::AlreadySignedUp),
+            Err(e) => Err(Error::Api(e)),
         }
     }
 
@@ -83,7 +282,7 @@ impl Client {
     ///
     /// #[tokio::main]
     ///     async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    ///     let client = Client::new("http://your.gotrue.endpoint".to_string());
+    ///     let mut client = Client::new("http://your.gotrue.endpoint".to_string());
     ///     let email = "some_email".to_string();
     ///     let password = "some_password".to_string();
     ///     let res = client
@@ -94,22 +293,59 @@ impl Client {
     pub async fn sign_in(
         &mut self,
         email_or_phone: EmailOrPhone,
-        password: &String,
+        password: &str,
+    ) -> Result<Session, Error> {
+        self.password_sign_in(email_or_phone, password, None).await
+    }
+
+    /// Signs in a user on a server with CAPTCHA protection enabled, see
+    /// [`Api::sign_in_with_captcha`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Client, EmailOrPhone};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = Client::new("http://your.gotrue.endpoint".to_string());
+    ///
+    ///     let email = "email@example.com".to_string();
+    ///     let result = client
+    ///         .sign_in_with_captcha(EmailOrPhone::Email(email), "Abcd1234!", "captcha-token")
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn sign_in_with_captcha(
+        &mut self,
+        email_or_phone: EmailOrPhone,
+        password: &str,
+        captcha_token: &str,
     ) -> Result<Session, Error> {
-        self.current_session = None;
-        let result = self.api.sign_in(email_or_phone, &password).await;
+        self.password_sign_in(email_or_phone, password, Some(captcha_token))
+            .await
+    }
+
+    async fn password_sign_in(
+        &mut self,
+        email_or_phone: EmailOrPhone,
+        password: &str,
+        captcha_token: Option<&str>,
+    ) -> Result<Session, Error> {
+        self.forget_session().await;
+        let result = self
+            .api
+            .password_sign_in(email_or_phone, password, captcha_token)
+            .await;
 
         match result {
             Ok(session) => {
-                self.current_session = Some(session.clone());
-                return Ok(session);
-            }
-            Err(e) => {
-                if e.is_status() && e.status().unwrap().as_str() == "400" {
-                    return Err(Error::WrongCredentials);
-                }
-                return Err(Error::InternalError);
+                self.set_current_session(Some(session.clone())).await;
+                self.emit(AuthChangeEvent::SignedIn(session.clone()));
+                Ok(session)
             }
+            Err(e) if e.status() == Some(400) => Err(Error::WrongCredentials),
+            Err(e) => Err(Error::Api(e)),
         }
     }
 
@@ -135,31 +371,70 @@ impl Client {
         email_or_phone: EmailOrPhone,
         should_create_user: Option<bool>,
     ) -> Result<bool, Error> {
-        let result = self.api.send_otp(email_or_phone, should_create_user).await;
+        let params = OtpParams {
+            should_create_user,
+            ..Default::default()
+        };
+
+        self.otp(email_or_phone, params).await
+    }
+
+    /// Sends an OTP Code on a server with CAPTCHA protection enabled, see
+    /// [`Api::send_otp_with_captcha`].
+    pub async fn send_otp_with_captcha(
+        &self,
+        email_or_phone: EmailOrPhone,
+        should_create_user: Option<bool>,
+        captcha_token: &str,
+    ) -> Result<bool, Error> {
+        let params = OtpParams {
+            should_create_user,
+            captcha_token: Some(captcha_token.to_string()),
+            ..Default::default()
+        };
+
+        self.otp(email_or_phone, params).await
+    }
+
+    /// Sends an OTP with metadata, a delivery channel or a redirect url, see
+    /// [`Api::send_otp_with`].
+    pub async fn send_otp_with(
+        &self,
+        email_or_phone: EmailOrPhone,
+        params: OtpParams,
+    ) -> Result<bool, Error> {
+        self.otp(email_or_phone, params).await
+    }
+
+    async fn otp(&self, email_or_phone: EmailOrPhone, params: OtpParams) -> Result<bool, Error> {
+        let result = self.api.otp(email_or_phone, params).await;
 
         match result {
-            Ok(_) => return Ok(true),
-            Err(e) => {
-                if e.is_status() && e.status().unwrap().as_str() == "422" {
-                    return Err(Error::UserNotFound);
-                }
-                return Err(Error::InternalError);
-            }
+            Ok(_) => Ok(true),
+            Err(e) if e.status() == Some(422) => Err(Error::UserNotFound),
+            Err(e) => Err(Error::Api(e)),
         }
     }
 
-    pub async fn verify_otp<T: serde::Serialize>(&mut self, params: T) -> Result<bool, Error> {
-        self.current_session = None;
+    /// Verifies an OTP, magic link or token hash and stores the resulting session.
+    pub async fn verify_otp_with(&mut self, params: VerifyOtpParams) -> Result<Session, Error> {
+        self.verify_otp(params).await
+    }
+
+    /// Verifies an OTP or magic link token and stores the resulting session.
+    /// Prefer [`Client::verify_otp_with`], this accepts any body and is meant as an escape hatch.
+    pub async fn verify_otp<T: serde::Serialize>(&mut self, params: T) -> Result<Session, Error> {
+        self.forget_session().await;
         let result = self.api.verify_otp(params).await;
 
         match result {
-            Ok(_) => return Ok(true),
-            Err(e) => {
-                if e.is_status() && e.status().unwrap().as_str() == "400" {
-                    return Err(Error::WrongToken);
-                }
-                return Err(Error::InternalError);
+            Ok(session) => {
+                self.set_current_session(Some(session.clone())).await;
+                self.emit(AuthChangeEvent::SignedIn(session.clone()));
+                Ok(session)
             }
+            Err(e) if e.status() == Some(400) => Err(Error::WrongToken),
+            Err(e) => Err(Error::Api(e)),
         }
     }
 
@@ -180,17 +455,58 @@ impl Client {
     ///     Ok(())
     /// }
     pub async fn sign_out(&self) -> Result<bool, Error> {
-        let result = match &self.current_session {
+        let result = match self.current_session() {
             Some(session) => self.api.sign_out(&session.access_token).await,
             None => return Err(Error::NotAuthenticated),
         };
 
         match result {
-            Ok(_) => return Ok(true),
-            Err(_) => return Err(Error::InternalError),
+            Ok(_) => {
+                self.set_current_session(None).await;
+                self.emit(AuthChangeEvent::SignedOut);
+                Ok(true)
+            }
+            Err(e) => Err(Error::Api(e)),
         }
     }
 
+    /// Sign out the current user of the sessions selected by `scope`
+    ///
+    /// With [`SignOutScope::Others`] the current session stays signed in, otherwise it is removed
+    /// like in [`Client::sign_out`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::{Client, SignOutScope};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::new("http://your.gotrue.endpoint".to_string());
+    ///
+    ///     // Sign in first
+    ///
+    ///     let result = client.sign_out_with_scope(SignOutScope::Global).await;
+    /// }
+    /// ```
+    pub async fn sign_out_with_scope(&self, scope: SignOutScope) -> Result<bool, Error> {
+        let session = match self.current_session() {
+            Some(session) => session,
+            None => return Err(Error::NotAuthenticated),
+        };
+
+        self.api
+            .sign_out_with_scope(&session.access_token, scope)
+            .await?;
+
+        if scope != SignOutScope::Others {
+            self.set_current_session(None).await;
+            self.emit(AuthChangeEvent::SignedOut);
+        }
+
+        Ok(true)
+    }
+
     /// Reset a user's password for an email address
     ///
     /// # Example
@@ -201,104 +517,405 @@ impl Client {
     /// #[tokio::main]
     ///     async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ///     let client = Client::new("http://your.gotrue.endpoint".to_string());
-    ///     let email = "some_email".to_string()
+    ///     let email = "some_email".to_string();
     ///
     ///     let res = client.reset_password_for_email(&email).await?;
     ///     Ok(())
     /// }
     pub async fn reset_password_for_email(&self, email: &str) -> Result<bool, Error> {
-        let result = self.api.reset_password_for_email(&email).await;
+        self.recover(email, None).await
+    }
+
+    /// Reset a user's password on a server with CAPTCHA protection enabled, see
+    /// [`Api::reset_password_for_email_with_captcha`].
+    pub async fn reset_password_for_email_with_captcha(
+        &self,
+        email: &str,
+        captcha_token: &str,
+    ) -> Result<bool, Error> {
+        self.recover(email, Some(captcha_token)).await
+    }
+
+    async fn recover(&self, email: &str, captcha_token: Option<&str>) -> Result<bool, Error> {
+        let result = self.api.recover(email, captcha_token).await;
 
         match result {
-            Ok(_) => return Ok(true),
-            Err(_) => return Err(Error::UserNotFound),
+            Ok(_) => Ok(true),
+            Err(e) if e.status() == Some(404) => Err(Error::UserNotFound),
+            Err(e) => Err(Error::Api(e)),
         }
     }
 
-    pub async fn update_user(&self, user: UserAttributes) -> Result<UserUpdate, Error> {
-        let session = match &self.current_session {
-            Some(s) => s,
-            None => return Err(Error::NotAuthenticated),
-        };
+    /// Gets the signed in user from the server using the current session
+    ///
+    /// An expired session is refreshed first if `auto_refresh_token` is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::new("http://your.gotrue.endpoint".to_string());
+    ///
+    ///     // sign in first
+    ///
+    ///     let result = client.get_user().await;
+    /// }
+    /// ```
+    pub async fn get_user(&self) -> Result<User, Error> {
+        let session = self.ensure_valid_session().await?;
+
+        match self.api.get_user(&session.access_token).await {
+            Ok(user) => Ok(user),
+            Err(e) if e.status() == Some(401) => Err(Error::WrongToken),
+            Err(e) => Err(Error::Api(e)),
+        }
+    }
+
+    pub async fn update_user(&mut self, user: UserAttributes) -> Result<UserUpdate, Error> {
+        let session = self.ensure_valid_session().await?;
 
         let result = self.api.update_user(user, &session.access_token).await;
 
         match result {
-            Ok(user) => return Ok(user),
-            Err(e) => {
-                if e.is_status() && e.status().unwrap().as_str() == "400" {
-                    return Err(Error::UserNotFound);
-                }
-                return Err(Error::InternalError);
+            Ok(user) => {
+                self.emit(AuthChangeEvent::UserUpdated(user.clone()));
+                Ok(user)
             }
+            Err(e) if e.status() == Some(400) => Err(Error::UserNotFound),
+            Err(e) => Err(Error::Api(e)),
         }
     }
 
+    /// Changes the password of the signed in user
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = Client::new("http://your.gotrue.endpoint".to_string());
+    ///
+    ///     // sign in first
+    ///
+    ///     let result = client.update_password("Abcd12345!").await;
+    /// }
+    /// ```
+    pub async fn update_password(&mut self, new_password: &str) -> Result<UserUpdate, Error> {
+        let attributes = UserAttributes {
+            password: Some(new_password.to_string()),
+            ..Default::default()
+        };
+
+        self.update_user(attributes).await
+    }
+
     /// Refreshes the current session
     ///
     /// # Example
     ///
     /// ```
-    /// use go_true::{Client};
+    /// use go_true::Client;
     ///
     /// #[tokio::main]
-    ///     async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    ///     let client = Client::new("http://your.gotrue.endpoint".to_string());
+    /// async fn main() {
+    ///     let mut client = Client::new("http://your.gotrue.endpoint".to_string());
     ///
     ///     // sign in first
     ///
-    ///     client.refresh_session().await?:
-    ///     Ok(())
+    ///     let result = client.refresh_session().await;
     /// }
+    /// ```
     pub async fn refresh_session(&mut self) -> Result<Session, Error> {
-        if self.current_session.is_none() {
-            return Err(Error::NotAuthenticated);
-        }
+        let previous = match self.current_session() {
+            Some(session) => session,
+            None => return Err(Error::NotAuthenticated),
+        };
+
+        let _guard = self.refresh_lock.lock().await;
 
-        let result = match &self.current_session {
+        // A refresh finished while we waited, spending the refresh token again would fail.
+        let result = match self.current_session() {
+            Some(session) if session.refresh_token != previous.refresh_token => return Ok(session),
             Some(session) => self.api.refresh_access_token(&session.refresh_token).await,
             None => return Err(Error::MissingRefreshToken),
         };
 
         let session = match result {
             Ok(session) => session,
-            Err(_) => return Err(Error::InternalError),
+            Err(e) => return Err(Error::SessionRefreshFailed(e)),
         };
 
-        self.current_session = Some(session.clone());
+        self.set_current_session(Some(session.clone())).await;
+        self.emit(AuthChangeEvent::TokenRefreshed(session.clone()));
 
-        return Ok(session);
+        Ok(session)
     }
 
-    /// Sets a session by refresh token
+    /// Requests a new session with a refresh token persisted by the application and makes it
+    /// the current session
+    ///
+    /// Unlike [`refresh_session`](Client::refresh_session) this doesn't need a current session,
+    /// e.g. on a cold start where only the refresh token was stored.
     ///
     /// # Example
     ///
     /// ```
-    /// use go_true::{Client};
+    /// use go_true::Client;
     ///
     /// #[tokio::main]
-    ///     async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    ///     let client = Client::new("http://your.gotrue.endpoint".to_string());
-    ///     let token = "refresh_token".to_string();
+    /// async fn main() {
+    ///     let mut client = Client::new("http://your.gotrue.endpoint".to_string());
     ///
-    ///     let session = client.set_session(token).await?:
-    ///     Ok(())
+    ///     let result = client.refresh_from_token("refresh_token").await;
     /// }
-    pub async fn set_session(&mut self, refresh_token: &str) -> Result<Session, Error> {
-        if refresh_token.len() < 1 {
+    /// ```
+    pub async fn refresh_from_token(&mut self, refresh_token: &str) -> Result<Session, Error> {
+        if refresh_token.is_empty() {
+            return Err(Error::MissingRefreshToken);
+        }
+
+        let _guard = self.refresh_lock.lock().await;
+
+        let session = match self.api.refresh_access_token(refresh_token).await {
+            Ok(session) => session,
+            Err(e) => return Err(Error::SessionRefreshFailed(e)),
+        };
+
+        self.set_current_session(Some(session.clone())).await;
+        self.emit(AuthChangeEvent::SignedIn(session.clone()));
+
+        Ok(session)
+    }
+
+    /// Restores a session from an access and refresh token persisted by the application
+    ///
+    /// The user is fetched with the access token. If the access token already expired, a new
+    /// session is requested with the refresh token instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = Client::new("http://your.gotrue.endpoint".to_string());
+    ///
+    ///     let result = client.set_session("access_token", "refresh_token").await;
+    /// }
+    /// ```
+    pub async fn set_session(
+        &mut self,
+        access_token: &str,
+        refresh_token: &str,
+    ) -> Result<Session, Error> {
+        if access_token.is_empty() || refresh_token.is_empty() {
             return Err(Error::NotAuthenticated);
         }
 
-        let result = self.api.refresh_access_token(refresh_token).await;
+        let claims = decode_jwt(access_token)?;
+        let now = session::now();
+        let expires_in =
+            claims.exp - now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as i64;
 
-        let session = match result {
+        let expired = expires_in <= Session::EXPIRY_LEEWAY.as_secs() as i64;
+
+        // Held until the new session is stored, like in `refresh_from_token`.
+        let _guard = if expired {
+            Some(self.refresh_lock.lock().await)
+        } else {
+            None
+        };
+
+        let session = if expired {
+            // Another task may have refreshed this user's session while we waited, spending the
+            // refresh token again would fail.
+            match self.current_session() {
+                Some(session) if session.user.id == claims.sub && !session.is_expired() => {
+                    return Ok(session)
+                }
+                _ => {}
+            }
+
+            match self.api.refresh_access_token(refresh_token).await {
+                Ok(session) => session,
+                Err(e) => return Err(Error::SessionRefreshFailed(e)),
+            }
+        } else {
+            let user = match self.api.get_user(access_token).await {
+                Ok(user) => user,
+                Err(e) if e.status() == Some(401) => return Err(Error::WrongToken),
+                Err(e) => return Err(Error::Api(e)),
+            };
+
+            Session {
+                access_token: access_token.to_string(),
+                token_type: String::from("bearer"),
+                expires_in: expires_in as i32,
+                refresh_token: refresh_token.to_string(),
+                user,
+                created_at: now,
+                extra: HashMap::new(),
+            }
+        };
+
+        self.set_current_session(Some(session.clone())).await;
+        self.emit(AuthChangeEvent::SignedIn(session.clone()));
+
+        Ok(session)
+    }
+
+    /// Sets the session from the url GoTrue redirected to after an OAuth sign in or a followed
+    /// email link, see [`parse_auth_callback`](crate::parse_auth_callback).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut client = Client::new("http://your.gotrue.endpoint".to_string());
+    ///
+    ///     let url = "https://example.com/welcome#access_token=access-token&refresh_token=refresh-token&expires_in=3600";
+    ///     let result = client.set_session_from_url(url).await;
+    /// }
+    /// ```
+    pub async fn set_session_from_url(&mut self, url: &str) -> Result<Session, Error> {
+        let callback = parse_auth_callback(url)?;
+
+        self.set_session(&callback.access_token, &callback.refresh_token)
+            .await
+    }
+
+    /// Returns the current session, refreshing it first if it is expired and
+    /// `auto_refresh_token` is enabled.
+    async fn ensure_valid_session(&self) -> Result<Session, Error> {
+        let session = match self.current_session() {
+            Some(session) => session,
+            None => return Err(Error::NotAuthenticated),
+        };
+
+        if !self.auto_refresh_token || !session.is_expired() {
+            return Ok(session);
+        }
+
+        let outcome = self.refresh_session_if_expired().await?;
+
+        Ok(outcome.session)
+    }
+
+    /// Returns the current session, refreshing it first if it is expired
+    ///
+    /// When several tasks call this at the same time only one refreshes the session, the others
+    /// wait for it and get the refreshed session with [`RefreshOutcome::refreshed`] set to `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::new("http://your.gotrue.endpoint".to_string());
+    ///
+    ///     // sign in first
+    ///
+    ///     if let Ok(outcome) = client.refresh_session_if_expired().await {
+    ///         if outcome.refreshed {
+    ///             println!("new access token: {}", outcome.session.access_token);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub async fn refresh_session_if_expired(&self) -> Result<RefreshOutcome, Error> {
+        let _guard = self.refresh_lock.lock().await;
+
+        // Checked after locking, another task may have refreshed the session while we waited.
+        let session = match self.current_session() {
+            Some(session) => session,
+            None => return Err(Error::NotAuthenticated),
+        };
+
+        if !session.is_expired() {
+            return Ok(RefreshOutcome {
+                session,
+                refreshed: false,
+            });
+        }
+
+        let session = match self.api.refresh_access_token(&session.refresh_token).await {
             Ok(session) => session,
-            Err(_) => return Err(Error::InternalError),
+            Err(e) => return Err(Error::SessionRefreshFailed(e)),
         };
 
-        self.current_session = Some(session.clone());
+        self.set_current_session(Some(session.clone())).await;
+        self.emit(AuthChangeEvent::TokenRefreshed(session.clone()));
+
+        Ok(RefreshOutcome {
+            session,
+            refreshed: true,
+        })
+    }
+
+    /// Starts a background task that refreshes the session `refresh_before` ahead of its expiry.
+    ///
+    /// The task follows sessions set by later calls such as `sign_in` and stops once the client
+    /// is dropped. Dropping the returned handle aborts it. Must be called from within a tokio runtime.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use go_true::Client;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::new("http://your.gotrue.endpoint".to_string());
+    ///     let handle = client.start_auto_refresh(Duration::from_secs(60));
+    /// }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start_auto_refresh(&self, refresh_before: Duration) -> AutoRefreshHandle {
+        let task = tokio::spawn(auto_refresh::run(
+            self.api.clone(),
+            Arc::downgrade(&self.current_session),
+            self.current_session.subscribe(),
+            self.session_store.clone(),
+            self.events.clone(),
+            self.refresh_lock.clone(),
+            refresh_before,
+        ));
+
+        AutoRefreshHandle::new(task)
+    }
+
+    /// Drops the current session before signing in again. In dry run mode nothing is sent, so
+    /// the session is kept.
+    async fn forget_session(&self) {
+        if !self.api.dry_run {
+            self.set_current_session(None).await;
+        }
+    }
+
+    async fn set_current_session(&self, session: Option<Session>) {
+        if let Some(store) = &self.session_store {
+            match &session {
+                Some(session) => store.save(session).await,
+                None => store.clear().await,
+            }
+        }
+
+        self.current_session.send_replace(session);
+    }
 
-        return Ok(session);
+    fn emit(&self, event: AuthChangeEvent) {
+        // Sending only fails if nobody is subscribed.
+        let _ = self.events.send(event);
     }
 }
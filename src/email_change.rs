@@ -0,0 +1,20 @@
+use serde::Deserialize;
+
+use crate::session::Session;
+
+/// Result of [`Api::verify_email_change`](crate::Api::verify_email_change).
+///
+/// With secure email change enabled GoTrue sends a link to both the current and the new
+/// address. Confirming the first one only yields [`EmailChangeResult::Pending`], confirming the
+/// second one completes the change.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum EmailChangeResult {
+    /// The email change is complete.
+    Confirmed(Box<Session>),
+    /// The other address still has to be confirmed. Contains the message sent by GoTrue.
+    Pending {
+        #[serde(alias = "message")]
+        msg: String,
+    },
+}
@@ -1,3 +1,7 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
 #[derive(Debug)]
 pub enum Error {
     AlreadySignedUp,
@@ -6,10 +10,31 @@ pub enum Error {
     NotAuthenticated,
     MissingRefreshToken,
     WrongToken,
-    InternalError,
+    Api(ApiError),
+    /// The session could not be refreshed, carrying the error of the refresh request.
+    SessionRefreshFailed(ApiError),
+    InvalidJwt(String),
+    /// The redirect url passed to [`parse_auth_callback`](crate::parse_auth_callback) doesn't
+    /// contain a session.
+    InvalidAuthCallback(String),
+    /// GoTrue reported an error in the redirect url, e.g. because the link expired.
+    AuthCallbackFailed {
+        code: String,
+        message: String,
+    },
+    /// The configuration read by [`Api::from_env`](crate::Api::from_env) or
+    /// [`AdminApi::from_env`](crate::AdminApi::from_env) is missing or invalid.
+    InvalidConfig(String),
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Api(e) | Error::SessionRefreshFailed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -20,7 +45,181 @@ impl std::fmt::Display for Error {
             Error::NotAuthenticated => write!(f, "User is not authenticated."),
             Error::MissingRefreshToken => write!(f, "Refresh Token is missing"),
             Error::WrongToken => write!(f, "Wrong token."),
-            Error::InternalError => write!(f, "GoTrue internal error"),
+            Error::Api(e) => write!(f, "{e}"),
+            Error::SessionRefreshFailed(e) => write!(f, "Session could not be refreshed: {e}"),
+            Error::InvalidJwt(reason) => write!(f, "Invalid JWT: {reason}"),
+            Error::InvalidAuthCallback(reason) => write!(f, "Invalid auth callback url: {reason}"),
+            Error::AuthCallbackFailed { code, message } => {
+                write!(f, "Auth callback failed ({code}): {message}")
+            }
+            Error::InvalidConfig(reason) => write!(f, "Invalid configuration: {reason}"),
+        }
+    }
+}
+
+impl From<ApiError> for Error {
+    fn from(e: ApiError) -> Self {
+        Error::Api(e)
+    }
+}
+
+/// Error returned by every [`Api`](crate::Api) method.
+#[derive(Debug)]
+pub enum ApiError {
+    /// The request could not be sent or the response could not be read.
+    Transport(reqwest::Error),
+    /// GoTrue answered with an error status. `code` and `message` are taken from the error body.
+    GoTrue {
+        status: u16,
+        code: String,
+        message: String,
+    },
+    /// The response body could not be deserialized into the expected type.
+    Deserialize(serde_json::Error),
+    /// The request was not sent because its parameters are invalid.
+    InvalidParams(String),
+    /// GoTrue answered with `429 Too Many Requests`. `retry_after` is taken from the
+    /// `Retry-After` header if present.
+    RateLimited { retry_after: Option<Duration> },
+    /// The response body isn't JSON, e.g. an HTML error page of a proxy in front of GoTrue.
+    /// `body_snippet` holds the start of the body.
+    UnexpectedResponse { status: u16, body_snippet: String },
+    /// GoTrue rejected the password as too weak. `reasons` lists why, e.g. `length`,
+    /// `characters` or `pwned`.
+    WeakPassword {
+        status: u16,
+        message: String,
+        reasons: Vec<String>,
+    },
+    /// The request was built but not sent because dry run is enabled, see
+    /// [`Api::with_dry_run`](crate::Api::with_dry_run).
+    DryRun(Box<reqwest::Request>),
+}
+
+impl ApiError {
+    /// Returns the HTTP status code if GoTrue answered with an error status.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ApiError::Transport(e) => e.status().map(|status| status.as_u16()),
+            ApiError::GoTrue { status, .. } | ApiError::WeakPassword { status, .. } => {
+                Some(*status)
+            }
+            ApiError::RateLimited { .. } => Some(429),
+            ApiError::UnexpectedResponse { status, .. } => Some(*status),
+            ApiError::Deserialize(_) | ApiError::InvalidParams(_) | ApiError::DryRun(_) => None,
+        }
+    }
+}
+
+impl std::error::Error for ApiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ApiError::Transport(e) => Some(e),
+            ApiError::Deserialize(e) => Some(e),
+            ApiError::GoTrue { .. }
+            | ApiError::InvalidParams(_)
+            | ApiError::RateLimited { .. }
+            | ApiError::UnexpectedResponse { .. }
+            | ApiError::WeakPassword { .. }
+            | ApiError::DryRun(_) => None,
         }
     }
 }
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ApiError::Transport(e) => write!(f, "Request failed: {e}"),
+            ApiError::GoTrue {
+                status,
+                code,
+                message,
+            } => write!(f, "GoTrue error {status} ({code}): {message}"),
+            ApiError::Deserialize(e) => write!(f, "Invalid response body: {e}"),
+            ApiError::InvalidParams(reason) => write!(f, "Invalid parameters: {reason}"),
+            ApiError::RateLimited {
+                retry_after: Some(retry_after),
+            } => write!(
+                f,
+                "Rate limited, retry after {} seconds",
+                retry_after.as_secs()
+            ),
+            ApiError::RateLimited { retry_after: None } => write!(f, "Rate limited"),
+            ApiError::UnexpectedResponse {
+                status,
+                body_snippet,
+            } => write!(f, "Unexpected non-JSON response {status}: {body_snippet}"),
+            ApiError::WeakPassword {
+                message, reasons, ..
+            } => write!(f, "Weak password ({}): {message}", reasons.join(", ")),
+            ApiError::DryRun(request) => write!(
+                f,
+                "Dry run, {} {} was not sent",
+                request.method(),
+                request.url().path()
+            ),
+        }
+    }
+}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        ApiError::Transport(e)
+    }
+}
+
+impl From<serde_json::Error> for ApiError {
+    fn from(e: serde_json::Error) -> Self {
+        ApiError::Deserialize(e)
+    }
+}
+
+/// Error body as sent by GoTrue.
+///
+/// Depending on the endpoint and server version GoTrue either answers with
+/// `{"error": "...", "error_description": "..."}` or `{"code": 400, "msg": "..."}`,
+/// so every field is optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[non_exhaustive]
+pub struct GoTrueErrorBody {
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+    pub error_code: Option<String>,
+    pub code: Option<serde_json::Value>,
+    pub msg: Option<String>,
+    pub message: Option<String>,
+    pub weak_password: Option<WeakPasswordBody>,
+}
+
+/// Reasons GoTrue sends along a `weak_password` error.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[non_exhaustive]
+pub struct WeakPasswordBody {
+    #[serde(default)]
+    pub reasons: Vec<String>,
+}
+
+impl GoTrueErrorBody {
+    /// Machine readable error code, e.g. `invalid_grant`.
+    pub fn code(&self) -> String {
+        self.error_code
+            .clone()
+            .or_else(|| self.error.clone())
+            .or_else(|| match &self.code {
+                Some(serde_json::Value::String(code)) => Some(code.clone()),
+                Some(code) => Some(code.to_string()),
+                None => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Human readable error message.
+    pub fn message(&self) -> String {
+        self.error_description
+            .clone()
+            .or_else(|| self.msg.clone())
+            .or_else(|| self.message.clone())
+            .or_else(|| self.error.clone())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,18 @@
+use crate::{session::Session, user_update::UserUpdate};
+
+/// Number of events buffered per subscriber before the oldest ones are dropped.
+pub(crate) const EVENT_CAPACITY: usize = 16;
+
+/// Change of the auth state of a [`Client`](crate::Client), received through
+/// [`Client::on_auth_state_change`](crate::Client::on_auth_state_change).
+#[derive(Debug, Clone)]
+pub enum AuthChangeEvent {
+    /// A new session was created, e.g. by signing in, signing up or verifying an OTP.
+    SignedIn(Session),
+    /// The session was cleared.
+    SignedOut,
+    /// The session was refreshed, either on demand or by the auto refresh task.
+    TokenRefreshed(Session),
+    /// The signed in user was updated.
+    UserUpdated(UserUpdate),
+}
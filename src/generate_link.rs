@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::user::User;
+
+/// Kind of link created by [`Api::generate_link`](crate::Api::generate_link).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerateLinkType {
+    Signup,
+    Invite,
+    #[serde(rename = "magiclink")]
+    MagicLink,
+    Recovery,
+    /// Link sent to the current address when changing the email.
+    EmailChangeCurrent,
+    /// Link sent to the new address when changing the email.
+    EmailChangeNew,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GenerateLinkParams {
+    #[serde(rename = "type")]
+    pub link_type: GenerateLinkType,
+    pub email: String,
+    /// Required for [`GenerateLinkType::Signup`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Required for the email change link types.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_email: Option<String>,
+    /// User metadata, used for [`GenerateLinkType::Signup`] and [`GenerateLinkType::Invite`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_to: Option<String>,
+}
+
+impl GenerateLinkParams {
+    pub fn new(link_type: GenerateLinkType, email: impl Into<String>) -> GenerateLinkParams {
+        GenerateLinkParams {
+            link_type,
+            email: email.into(),
+            password: None,
+            new_email: None,
+            data: None,
+            redirect_to: None,
+        }
+    }
+}
+
+/// The generated link along with the user it was created for.
+#[derive(Debug, Clone, Deserialize)]
+#[non_exhaustive]
+pub struct GenerateLinkResponse {
+    pub action_link: String,
+    pub email_otp: Option<String>,
+    pub hashed_token: Option<String>,
+    pub verification_type: Option<String>,
+    pub redirect_to: Option<String>,
+    #[serde(flatten)]
+    pub user: User,
+}
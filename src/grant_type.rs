@@ -0,0 +1,30 @@
+use std::fmt;
+
+/// Grant type of a `/token` request, each exchanging a different credential for a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrantType {
+    Password,
+    RefreshToken,
+    /// Auth code and verifier of the PKCE flow.
+    Pkce,
+    /// ID token issued by an OIDC provider.
+    IdToken,
+}
+
+impl GrantType {
+    /// Value of the `grant_type` query parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GrantType::Password => "password",
+            GrantType::RefreshToken => "refresh_token",
+            GrantType::Pkce => "pkce",
+            GrantType::IdToken => "id_token",
+        }
+    }
+}
+
+impl fmt::Display for GrantType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+/// Server status as returned by `/health`.
+#[derive(Debug, Clone, Deserialize)]
+#[non_exhaustive]
+pub struct HealthStatus {
+    pub name: String,
+    pub version: String,
+    pub description: String,
+}
@@ -0,0 +1,33 @@
+use serde::Serialize;
+
+use crate::provider::Provider;
+
+/// Parameters of [`Api::sign_in_with_id_token`](crate::Api::sign_in_with_id_token).
+///
+/// # Example
+///
+/// ```
+/// use go_true::{IdTokenParams, Provider};
+///
+/// let params = IdTokenParams {
+///     provider: Provider::Google,
+///     token: "id.token.from-google-sdk".to_string(),
+///     access_token: None,
+///     nonce: Some("raw-nonce".to_string()),
+/// };
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct IdTokenParams {
+    /// Provider that issued the token, see [`Provider::supports_id_token`].
+    pub provider: Provider,
+    /// OIDC ID token returned by the provider's native SDK.
+    #[serde(rename = "id_token")]
+    pub token: String,
+    /// Access token issued together with the ID token, required if the ID token contains an
+    /// `at_hash` claim.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_token: Option<String>,
+    /// Raw nonce, if a hash of it was passed to the provider when requesting the ID token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::timestamp::Timestamp;
+
+/// An identity linked to a user, e.g. an email address or a Google or GitHub account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Identity {
+    /// Id of the identity at the provider.
+    pub id: String,
+    /// Unique id of the identity, passed to [`Api::unlink_identity`](crate::Api::unlink_identity).
+    #[serde(default)]
+    pub identity_id: Option<String>,
+    pub user_id: String,
+    pub provider: String,
+    #[serde(default)]
+    pub identity_data: Value,
+    pub last_sign_in_at: Option<Timestamp>,
+    pub created_at: Option<Timestamp>,
+    pub updated_at: Option<Timestamp>,
+}
+
+/// Identities as contained in the user returned by `/user` and `/admin/users/{id}`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct UserIdentities {
+    #[serde(default)]
+    pub identities: Option<Vec<Identity>>,
+}
+
+/// Response of `/user/identities/authorize` and `/sso` when the redirect is skipped.
+#[derive(Debug, Deserialize)]
+pub(crate) struct AuthorizeUrl {
+    pub url: String,
+}
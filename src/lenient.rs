@@ -0,0 +1,6 @@
+//! Adapters used with the `lenient` feature to accept responses of other GoTrue versions.
+
+use serde_with::{As, DisplayFromStr, PickFirst, Same};
+
+/// Accepts a number either as JSON number or as string, e.g. `"expires_in": "3600"`.
+pub(crate) type NumberOrString = As<PickFirst<(Same, DisplayFromStr)>>;
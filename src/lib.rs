@@ -24,27 +24,133 @@
 //!     let email = "email@example.com".to_string();
 //!     let password = "Abcd1234!".to_string();
 //!
-//!     let session = client.sign_up(EmailOrPhone::Email(email), &password).await;
+//!     let result = client.sign_up(EmailOrPhone::Email(email), &password).await;
 //!
-//!     println!("{:?}", session);
+//!     println!("{:?}", result);
 //! }
 //! ```
 //!
+//! ## Features
+//!
+//! - `native-tls` (default): Uses the platform's TLS implementation, OpenSSL on Linux.
+//! - `rustls-tls`: Uses rustls instead, e.g. for static musl builds. Disable the default
+//!   features to drop the OpenSSL dependency. When both are enabled rustls is used.
+//! - `blocking`: Enables the synchronous `blocking::Api` for use outside of an async runtime.
+//! - `verify`: Enables `Api::verify_jwt` to verify access tokens locally with the JWT secret.
+//! - `chrono`: Deserializes timestamps like `User::created_at` into `chrono::DateTime<Utc>`
+//!   instead of strings, see [`Timestamp`].
+//! - `tracing`: Emits a `gotrue.request` span with method, path, status and duration for every
+//!   request of [`Api`], [`AdminApi`] and [`Client`]. Tokens and passwords are never recorded.
+//! - `test-util`: Enables `test_util::MockServer`, a local stand-in for GoTrue with canned
+//!   responses, and `User`/`Session` fixtures to test code using this crate without a server.
+//! - `lenient`: Tolerates minor differences between GoTrue versions when deserializing
+//!   responses, e.g. `Session::expires_in` sent as a string or a missing `User::aud`.
+//!
+//! ## WASM
+//!
+//! [`Api`] and [`Client`] compile for `wasm32-unknown-unknown` using the fetch based backend of
+//! reqwest. Timeouts, retries, auto refresh and [`FileSessionStore`] are not available there.
+//!
 //! Check out the [README][readme] for more info.
 //!
 //! [gotrue]: https://github.com/supabase/gotrue
 //! [readme]: https://github.com/fubinator/gotrue-rs
 
+mod admin;
+mod admin_user_attributes;
 mod api;
+mod api_builder;
+mod auth_callback;
+#[cfg(not(target_arch = "wasm32"))]
+mod auto_refresh;
+mod ban_duration;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod claims;
 mod client;
+mod email_change;
 pub mod error;
+mod events;
+mod generate_link;
+mod grant_type;
+mod health;
+mod id_token_params;
+mod identity;
+#[cfg(feature = "lenient")]
+mod lenient;
+mod list_users_params;
+mod mfa;
+mod oauth_options;
+mod otp_params;
+mod paginated_users;
+mod pkce;
+mod provider;
+mod refresh_outcome;
+#[cfg(not(target_arch = "wasm32"))]
+mod response_hook;
+#[cfg(not(target_arch = "wasm32"))]
+mod retry;
 mod session;
+mod session_store;
+mod settings;
+mod sign_out_scope;
+mod sign_up_options;
+mod sign_up_result;
+mod sso;
+#[cfg(all(feature = "test-util", not(target_arch = "wasm32")))]
+pub mod test_util;
+mod timestamp;
 mod user;
 mod user_attributes;
 mod user_list;
+mod user_session;
 mod user_update;
+mod verify_otp_params;
 
+pub use admin::AdminApi;
+pub use admin_user_attributes::AdminUserAttributes;
 pub use api::Api;
 pub use api::EmailOrPhone;
+pub use api::ResendType;
+pub use api_builder::ApiBuilder;
+pub use auth_callback::{parse_auth_callback, AuthCallback};
+#[cfg(not(target_arch = "wasm32"))]
+pub use auto_refresh::AutoRefreshHandle;
+pub use ban_duration::BanDuration;
+pub use claims::{decode_jwt, Claims};
 pub use client::Client;
+pub use email_change::EmailChangeResult;
+pub use events::AuthChangeEvent;
+pub use generate_link::{GenerateLinkParams, GenerateLinkResponse, GenerateLinkType};
+pub use grant_type::GrantType;
+pub use health::HealthStatus;
+pub use id_token_params::IdTokenParams;
+pub use identity::Identity;
+pub use list_users_params::ListUsersParams;
+pub use mfa::{
+    Factor, FactorStatus, FactorType, MfaChallengeResponse, MfaEnrollParams, MfaEnrollResponse,
+    TotpDetails,
+};
+pub use oauth_options::{OAuthOptions, OAuthResponse};
+pub use otp_params::{OtpChannel, OtpParams};
+pub use paginated_users::PaginatedUsers;
+pub use pkce::PkceChallenge;
+pub use provider::Provider;
+pub use refresh_outcome::RefreshOutcome;
+#[cfg(not(target_arch = "wasm32"))]
+pub use retry::RetryConfig;
+pub use session::Session;
+#[cfg(not(target_arch = "wasm32"))]
+pub use session_store::FileSessionStore;
+pub use session_store::SessionStore;
+pub use settings::Settings;
+pub use sign_out_scope::SignOutScope;
+pub use sign_up_options::SignUpOptions;
+pub use sign_up_result::SignUpResult;
+pub use sso::{SamlProvider, SsoDomain, SsoParams, SsoProvider, SsoProviderAttributes};
+pub use timestamp::Timestamp;
+pub use user::User;
 pub use user_attributes::UserAttributes;
+pub use user_session::UserSession;
+pub use user_update::UserUpdate;
+pub use verify_otp_params::{OtpType, VerifyOtpParams};
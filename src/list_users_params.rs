@@ -0,0 +1,44 @@
+use serde::Serialize;
+
+/// Query parameters for [`Api::list_users_with`](crate::Api::list_users_with).
+///
+/// # Example
+///
+/// ```
+/// use go_true::ListUsersParams;
+///
+/// let params = ListUsersParams::new().page(2).per_page(50).filter("example.com");
+/// ```
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListUsersParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+}
+
+impl ListUsersParams {
+    pub fn new() -> ListUsersParams {
+        ListUsersParams::default()
+    }
+
+    /// Page to return, starting at 1.
+    pub fn page(mut self, page: u32) -> Self {
+        self.page = Some(page);
+        self
+    }
+
+    /// Number of users per page.
+    pub fn per_page(mut self, per_page: u32) -> Self {
+        self.per_page = Some(per_page);
+        self
+    }
+
+    /// Only return users whose email or name contains `filter`.
+    pub fn filter(mut self, filter: impl Into<String>) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use crate::timestamp::Timestamp;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FactorType {
+    Totp,
+}
+
+/// Parameters for [`Api::mfa_enroll`](crate::Api::mfa_enroll).
+#[derive(Debug, Clone, Serialize)]
+pub struct MfaEnrollParams {
+    pub factor_type: FactorType,
+    /// Name shown to the user to tell their factors apart.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub friendly_name: Option<String>,
+    /// Issuer shown in the authenticator app.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[non_exhaustive]
+pub struct MfaEnrollResponse {
+    /// Id of the new, not yet verified factor.
+    pub id: String,
+    #[serde(rename = "type")]
+    pub factor_type: FactorType,
+    pub totp: TotpDetails,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[non_exhaustive]
+pub struct TotpDetails {
+    /// QR code containing `uri` as SVG data URL.
+    pub qr_code: String,
+    pub secret: String,
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[non_exhaustive]
+pub struct MfaChallengeResponse {
+    /// Id of the challenge, passed to [`Api::mfa_verify`](crate::Api::mfa_verify).
+    pub id: String,
+    /// Unix timestamp after which the challenge can no longer be verified.
+    #[cfg_attr(feature = "lenient", serde(with = "crate::lenient::NumberOrString"))]
+    pub expires_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FactorStatus {
+    Verified,
+    Unverified,
+}
+
+/// An MFA factor enrolled by a user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Factor {
+    pub id: String,
+    pub friendly_name: Option<String>,
+    pub factor_type: FactorType,
+    pub status: FactorStatus,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+/// Factors as contained in the user returned by `/user`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct UserFactors {
+    #[serde(default)]
+    pub factors: Option<Vec<Factor>>,
+}
@@ -0,0 +1,25 @@
+/// Options for the OAuth authorization URL.
+#[derive(Debug, Clone, Default)]
+pub struct OAuthOptions {
+    /// URL the user is sent to after signing in with the provider.
+    pub redirect_to: Option<String>,
+    /// Space separated list of scopes requested from the provider.
+    pub scopes: Option<String>,
+    /// Additional provider specific query parameters.
+    pub query_params: Vec<(String, String)>,
+    /// Uses the implicit flow instead of PKCE in
+    /// [`Api::sign_in_with_oauth`](crate::Api::sign_in_with_oauth), the session is then returned
+    /// in the fragment of the redirect URL.
+    pub skip_pkce: bool,
+}
+
+/// Authorization URL returned by [`Api::sign_in_with_oauth`](crate::Api::sign_in_with_oauth).
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct OAuthResponse {
+    /// URL to send the user to.
+    pub url: String,
+    /// Verifier to pass to [`Api::exchange_code_for_session`](crate::Api::exchange_code_for_session)
+    /// along with the auth code of the callback, `None` if PKCE was skipped.
+    pub code_verifier: Option<String>,
+}
@@ -0,0 +1,40 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// Channel an OTP sent to a phone number is delivered through.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OtpChannel {
+    #[default]
+    Sms,
+    Whatsapp,
+}
+
+/// Options for [`Api::send_otp_with`](crate::Api::send_otp_with).
+///
+/// # Example
+///
+/// ```
+/// use go_true::{OtpChannel, OtpParams};
+/// use serde_json::json;
+///
+/// let params = OtpParams {
+///     channel: Some(OtpChannel::Whatsapp),
+///     data: Some(json!({ "referrer": "newsletter" })),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OtpParams {
+    /// Set to `Some(false)` to only send OTPs to existing users.
+    pub should_create_user: Option<bool>,
+    /// Stored as `user_metadata` if the user is created by this call, ignored otherwise.
+    pub data: Option<Value>,
+    /// Only used for phone numbers, GoTrue defaults to SMS.
+    pub channel: Option<OtpChannel>,
+    /// URL the magic link returns the user to, overrides
+    /// [`Api::with_redirect_to`](crate::Api::with_redirect_to).
+    pub redirect_to: Option<String>,
+    /// Required on servers with CAPTCHA protection enabled.
+    pub captcha_token: Option<String>,
+}
@@ -0,0 +1,59 @@
+use reqwest::header::HeaderMap;
+use url::Url;
+
+use crate::user::User;
+
+/// A page of users together with the pagination info GoTrue sends in the response headers.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PaginatedUsers {
+    pub users: Vec<User>,
+    /// Total number of users, taken from the `X-Total-Count` header.
+    pub total: Option<u64>,
+    /// Number of the next page, taken from the `Link` header. `None` on the last page.
+    pub next_page: Option<u32>,
+}
+
+impl PaginatedUsers {
+    pub(crate) fn new(users: Vec<User>, headers: &HeaderMap) -> PaginatedUsers {
+        let total = headers
+            .get("x-total-count")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok());
+
+        let next_page = headers
+            .get("link")
+            .and_then(|value| value.to_str().ok())
+            .and_then(next_page_from_link);
+
+        PaginatedUsers {
+            users,
+            total,
+            next_page,
+        }
+    }
+}
+
+/// Reads the page number of the `rel="next"` entry of a `Link` header, e.g.
+/// `</admin/users?page=2&per_page=50>; rel="next", </admin/users?page=4&per_page=50>; rel="last"`.
+fn next_page_from_link(link: &str) -> Option<u32> {
+    let target = link.split(',').find_map(|entry| {
+        let (target, params) = entry.split_once(';')?;
+        let is_next = params
+            .split(';')
+            .any(|param| param.trim().replace(' ', "") == "rel=\"next\"");
+
+        is_next.then(|| target.trim().trim_start_matches('<').trim_end_matches('>'))
+    })?;
+
+    // The target may be relative, the base is only needed to parse the query.
+    let url = Url::parse("http://localhost").ok()?.join(target).ok()?;
+    let page = url
+        .query_pairs()
+        .find(|(name, _)| name == "page")?
+        .1
+        .parse()
+        .ok()?;
+
+    Some(page)
+}
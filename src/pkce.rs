@@ -0,0 +1,42 @@
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+
+/// Length of the generated code verifier, RFC 7636 allows 43 to 128 characters.
+const VERIFIER_LENGTH: usize = 64;
+
+/// Code verifier and challenge for the OAuth PKCE flow.
+///
+/// The `code_challenge` is sent along with the authorization request while the `code_verifier`
+/// has to be kept by the caller until the auth code is exchanged for a session.
+#[derive(Debug, Clone)]
+pub struct PkceChallenge {
+    pub code_verifier: String,
+    pub code_challenge: String,
+}
+
+impl PkceChallenge {
+    /// Generates a random code verifier and its S256 code challenge.
+    pub fn new() -> PkceChallenge {
+        let code_verifier: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(VERIFIER_LENGTH)
+            .map(char::from)
+            .collect();
+
+        let code_challenge = base64::encode_config(
+            Sha256::digest(code_verifier.as_bytes()),
+            base64::URL_SAFE_NO_PAD,
+        );
+
+        PkceChallenge {
+            code_verifier,
+            code_challenge,
+        }
+    }
+}
+
+impl Default for PkceChallenge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
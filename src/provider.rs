@@ -0,0 +1,119 @@
+use std::fmt;
+
+use serde::{Serialize, Serializer};
+
+/// OAuth provider supported by GoTrue.
+///
+/// Converting from a string maps the lowercase provider names to their variant and keeps any
+/// other name as [`Provider::Custom`], so existing `&str` callers keep working.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Provider {
+    Apple,
+    Azure,
+    Bitbucket,
+    Discord,
+    Facebook,
+    Figma,
+    Github,
+    Gitlab,
+    Google,
+    Kakao,
+    Keycloak,
+    Linkedin,
+    Notion,
+    Slack,
+    Spotify,
+    Twitch,
+    Twitter,
+    WorkOS,
+    Zoom,
+    /// A provider without a variant, sent to GoTrue as is.
+    Custom(String),
+}
+
+impl Provider {
+    /// Name of the provider as expected by GoTrue.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Provider::Apple => "apple",
+            Provider::Azure => "azure",
+            Provider::Bitbucket => "bitbucket",
+            Provider::Discord => "discord",
+            Provider::Facebook => "facebook",
+            Provider::Figma => "figma",
+            Provider::Github => "github",
+            Provider::Gitlab => "gitlab",
+            Provider::Google => "google",
+            Provider::Kakao => "kakao",
+            Provider::Keycloak => "keycloak",
+            Provider::Linkedin => "linkedin_oidc",
+            Provider::Notion => "notion",
+            Provider::Slack => "slack_oidc",
+            Provider::Spotify => "spotify",
+            Provider::Twitch => "twitch",
+            Provider::Twitter => "twitter",
+            Provider::WorkOS => "workos",
+            Provider::Zoom => "zoom",
+            Provider::Custom(name) => name,
+        }
+    }
+
+    /// Returns whether GoTrue accepts ID tokens issued by this provider, see
+    /// [`Api::sign_in_with_id_token`](crate::Api::sign_in_with_id_token).
+    pub fn supports_id_token(&self) -> bool {
+        matches!(
+            self,
+            Provider::Apple
+                | Provider::Azure
+                | Provider::Facebook
+                | Provider::Google
+                | Provider::Kakao
+                | Provider::Keycloak
+        )
+    }
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Provider {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl From<&str> for Provider {
+    fn from(name: &str) -> Self {
+        match name {
+            "apple" => Provider::Apple,
+            "azure" => Provider::Azure,
+            "bitbucket" => Provider::Bitbucket,
+            "discord" => Provider::Discord,
+            "facebook" => Provider::Facebook,
+            "figma" => Provider::Figma,
+            "github" => Provider::Github,
+            "gitlab" => Provider::Gitlab,
+            "google" => Provider::Google,
+            "kakao" => Provider::Kakao,
+            "keycloak" => Provider::Keycloak,
+            "linkedin_oidc" => Provider::Linkedin,
+            "notion" => Provider::Notion,
+            "slack_oidc" => Provider::Slack,
+            "spotify" => Provider::Spotify,
+            "twitch" => Provider::Twitch,
+            "twitter" => Provider::Twitter,
+            "workos" => Provider::WorkOS,
+            "zoom" => Provider::Zoom,
+            name => Provider::Custom(name.to_string()),
+        }
+    }
+}
+
+impl From<String> for Provider {
+    fn from(name: String) -> Self {
+        Provider::from(name.as_str())
+    }
+}
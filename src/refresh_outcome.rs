@@ -0,0 +1,12 @@
+use crate::session::Session;
+
+/// Result of [`Client::refresh_session_if_expired`](crate::Client::refresh_session_if_expired).
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RefreshOutcome {
+    /// The current session, valid for at least [`Session::EXPIRY_LEEWAY`].
+    pub session: Session,
+    /// `true` if this call refreshed the session and the access token changed, `false` if the
+    /// session was still valid or another task refreshed it in the meantime.
+    pub refreshed: bool,
+}
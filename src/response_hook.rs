@@ -0,0 +1,25 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use reqwest::StatusCode;
+
+type HookFn = dyn Fn(&str, StatusCode, Duration) + Send + Sync;
+
+/// Callback registered with [`ApiBuilder::on_response`](crate::ApiBuilder::on_response).
+#[derive(Clone)]
+pub(crate) struct ResponseHook(Arc<HookFn>);
+
+impl ResponseHook {
+    pub(crate) fn new(hook: impl Fn(&str, StatusCode, Duration) + Send + Sync + 'static) -> Self {
+        ResponseHook(Arc::new(hook))
+    }
+
+    pub(crate) fn call(&self, path: &str, status: StatusCode, duration: Duration) {
+        (self.0)(path, status, duration)
+    }
+}
+
+impl fmt::Debug for ResponseHook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ResponseHook")
+    }
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::error::ApiError;
+
+/// Retry policy for idempotent requests such as `get_user`, `list_users` and
+/// `refresh_access_token`.
+///
+/// Requests are retried on connection errors, timeouts, 5xx and 429 responses using
+/// exponential backoff with jitter. A `Retry-After` sent with a 429 is waited for at least,
+/// unless it exceeds the deadline.
+///
+/// # Example
+///
+/// ```
+/// use go_true::{Api, RetryConfig};
+/// use std::time::Duration;
+///
+/// let client = Api::new("http://your.gotrue.endpoint".to_string()).with_retry(RetryConfig {
+///     max_retries: 5,
+///     base_delay: Duration::from_millis(200),
+///     max_delay: Duration::from_secs(5),
+///     deadline: Duration::from_secs(30),
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Maximum number of retries after the first attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on every further retry.
+    pub base_delay: Duration,
+    /// Upper bound for the delay between two attempts.
+    pub max_delay: Duration,
+    /// Overall time after which no further retry is started.
+    pub deadline: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+            deadline: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Delay before retry number `attempt` (starting at 0), between half and the full backoff.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+
+        let half = backoff / 2;
+        half + half.mul_f64(rand::thread_rng().gen::<f64>())
+    }
+}
+
+pub(crate) fn is_retryable(error: &ApiError) -> bool {
+    match error {
+        ApiError::Transport(e) => e.is_connect() || e.is_timeout(),
+        ApiError::GoTrue { status, .. } | ApiError::UnexpectedResponse { status, .. } => {
+            *status >= 500
+        }
+        ApiError::RateLimited { .. } => true,
+        ApiError::Deserialize(_)
+        | ApiError::InvalidParams(_)
+        | ApiError::WeakPassword { .. }
+        | ApiError::DryRun(_) => false,
+    }
+}
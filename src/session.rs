@@ -1,12 +1,77 @@
-use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
 
-use crate::user::User;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Deserialize)]
+use crate::{
+    claims::{self, Claims},
+    error::Error,
+    user::User,
+};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct Session {
     pub access_token: String,
+    #[cfg_attr(feature = "lenient", serde(default))]
     pub token_type: String,
+    #[cfg_attr(feature = "lenient", serde(with = "crate::lenient::NumberOrString"))]
     pub expires_in: i32,
     pub refresh_token: String,
     pub user: User,
+    /// Point in time the session was received, `expires_in` is counted from here.
+    #[serde(default = "now")]
+    pub created_at: SystemTime,
+    /// Fields sent by GoTrue that are not modelled by this struct yet, e.g. `provider_token`.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl Session {
+    /// Leeway used by [`Session::is_expired`] so tokens are not sent while they are about to expire.
+    pub const EXPIRY_LEEWAY: Duration = Duration::from_secs(10);
+
+    /// Returns the point in time the access token expires.
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        let expires_in = u64::try_from(self.expires_in).ok()?;
+
+        self.created_at.checked_add(Duration::from_secs(expires_in))
+    }
+
+    /// Returns true if the access token is expired or expires within [`Session::EXPIRY_LEEWAY`].
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_with_leeway(Self::EXPIRY_LEEWAY)
+    }
+
+    /// Returns true if the access token is expired or expires within `leeway`.
+    pub fn is_expired_with_leeway(&self, leeway: Duration) -> bool {
+        match self.time_until_expiry() {
+            Some(remaining) => remaining <= leeway,
+            None => true,
+        }
+    }
+
+    /// Returns the time left until the access token expires, or `None` if it already expired.
+    pub fn time_until_expiry(&self) -> Option<Duration> {
+        self.expires_at()?.duration_since(now()).ok()
+    }
+
+    /// Decodes the claims of the access token without verifying its signature, see [`decode_jwt`](crate::decode_jwt).
+    pub fn decode_claims(&self) -> Result<Claims, Error> {
+        claims::decode_jwt(&self.access_token)
+    }
+}
+
+/// Current time. `SystemTime::now` panics on WASM, so the time is taken from the browser there.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now() -> SystemTime {
+    SystemTime::now()
+}
+
+/// Current time. `SystemTime::now` panics on WASM, so the time is taken from the browser there.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now() -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_millis(js_sys::Date::now() as u64)
 }
@@ -0,0 +1,55 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+use crate::session::Session;
+
+/// Persists the session of a [`Client`](crate::Client), e.g. to keep a user signed in across restarts.
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    /// Loads the stored session, if any.
+    async fn load(&self) -> Option<Session>;
+
+    /// Stores the given session, replacing any previously stored one.
+    async fn save(&self, session: &Session);
+
+    /// Removes the stored session.
+    async fn clear(&self);
+}
+
+/// [`SessionStore`] that keeps the session as JSON in a file.
+///
+/// I/O errors are ignored: a session that can't be read is treated as missing.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct FileSessionStore {
+    path: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileSessionStore {
+    pub fn new(path: impl Into<PathBuf>) -> FileSessionStore {
+        FileSessionStore { path: path.into() }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+impl SessionStore for FileSessionStore {
+    async fn load(&self) -> Option<Session> {
+        let content = tokio::fs::read(&self.path).await.ok()?;
+
+        serde_json::from_slice(&content).ok()
+    }
+
+    async fn save(&self, session: &Session) {
+        if let Ok(content) = serde_json::to_vec(session) {
+            let _ = tokio::fs::write(&self.path, content).await;
+        }
+    }
+
+    async fn clear(&self) {
+        let _ = tokio::fs::remove_file(&self.path).await;
+    }
+}
@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// Auth configuration of the server as returned by `/settings`.
+#[derive(Debug, Clone, Deserialize)]
+#[non_exhaustive]
+pub struct Settings {
+    /// External providers mapped to whether they are enabled, e.g. `"github": true`.
+    #[serde(default)]
+    pub external: HashMap<String, bool>,
+    #[serde(default)]
+    pub disable_signup: bool,
+    #[serde(default)]
+    pub mailer_autoconfirm: bool,
+    #[serde(default)]
+    pub phone_autoconfirm: bool,
+    /// Sent by older GoTrue versions instead of `mailer_autoconfirm`.
+    #[serde(default)]
+    pub autoconfirm: bool,
+    pub sms_provider: Option<String>,
+}
+
+impl Settings {
+    /// Returns `true` if the given external provider is enabled on the server.
+    pub fn is_provider_enabled(&self, provider: &str) -> bool {
+        self.external.get(provider).copied().unwrap_or(false)
+    }
+}
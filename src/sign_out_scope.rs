@@ -0,0 +1,30 @@
+use std::fmt;
+
+/// Sessions ended by [`Api::sign_out_with_scope`](crate::Api::sign_out_with_scope).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SignOutScope {
+    /// Ends all sessions of the user, on every device. This is what GoTrue does without a scope.
+    #[default]
+    Global,
+    /// Ends only the session the access token belongs to.
+    Local,
+    /// Ends all sessions of the user except the one the access token belongs to.
+    Others,
+}
+
+impl SignOutScope {
+    /// Value of the `scope` query parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignOutScope::Global => "global",
+            SignOutScope::Local => "local",
+            SignOutScope::Others => "others",
+        }
+    }
+}
+
+impl fmt::Display for SignOutScope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
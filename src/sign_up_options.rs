@@ -0,0 +1,26 @@
+use serde_json::Value;
+
+/// Options for [`Api::sign_up_with`](crate::Api::sign_up_with).
+///
+/// # Example
+///
+/// ```
+/// use go_true::SignUpOptions;
+/// use serde_json::json;
+///
+/// let options = SignUpOptions {
+///     data: Some(json!({ "display_name": "Jane", "plan": "pro" })),
+///     redirect_to: Some("https://staging.example.com/welcome".to_string()),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SignUpOptions {
+    /// Stored as `user_metadata` of the new user.
+    pub data: Option<Value>,
+    /// URL the confirmation link returns the user to, overrides
+    /// [`Api::with_redirect_to`](crate::Api::with_redirect_to).
+    pub redirect_to: Option<String>,
+    /// Required on servers with CAPTCHA protection enabled.
+    pub captcha_token: Option<String>,
+}
@@ -0,0 +1,42 @@
+use serde::Deserialize;
+
+use crate::{session::Session, user::User};
+
+/// Result of [`Api::sign_up`](crate::Api::sign_up).
+///
+/// Whether the user is signed in right away depends on the server: with email or phone
+/// confirmation enabled GoTrue only returns the new user, who has to confirm first.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum SignUpResult {
+    /// The user is signed up and signed in.
+    Session(Session),
+    /// The user has to confirm their email or phone before signing in.
+    ConfirmationRequired(User),
+}
+
+impl SignUpResult {
+    /// Returns the signed up user.
+    pub fn user(&self) -> &User {
+        match self {
+            SignUpResult::Session(session) => &session.user,
+            SignUpResult::ConfirmationRequired(user) => user,
+        }
+    }
+
+    /// Returns the session if the user was signed in right away.
+    pub fn session(&self) -> Option<&Session> {
+        match self {
+            SignUpResult::Session(session) => Some(session),
+            SignUpResult::ConfirmationRequired(_) => None,
+        }
+    }
+
+    /// Converts into the session if the user was signed in right away.
+    pub fn into_session(self) -> Option<Session> {
+        match self {
+            SignUpResult::Session(session) => Some(session),
+            SignUpResult::ConfirmationRequired(_) => None,
+        }
+    }
+}
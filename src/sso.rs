@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::timestamp::Timestamp;
+
+/// Parameters of [`Api::sign_in_with_sso`](crate::Api::sign_in_with_sso).
+///
+/// Either `domain` or `provider_id` identifies the SAML identity provider to sign in with.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SsoParams {
+    /// Domain mapped to an identity provider, usually the domain of the user's email.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    /// Id of the identity provider as registered with GoTrue.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_to: Option<String>,
+}
+
+impl SsoParams {
+    pub fn for_domain(domain: impl Into<String>) -> SsoParams {
+        SsoParams {
+            domain: Some(domain.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn for_provider_id(provider_id: impl Into<String>) -> SsoParams {
+        SsoParams {
+            provider_id: Some(provider_id.into()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Body of `/sso`, asking GoTrue to return the redirect URL instead of redirecting.
+#[derive(Debug, Serialize)]
+pub(crate) struct SsoRequest<'a> {
+    #[serde(flatten)]
+    pub params: &'a SsoParams,
+    pub skip_http_redirect: bool,
+}
+
+/// A SAML identity provider registered with GoTrue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SsoProvider {
+    pub id: String,
+    pub saml: Option<SamlProvider>,
+    /// Domains mapped to this provider, used by [`SsoParams::domain`].
+    #[serde(default)]
+    pub domains: Vec<SsoDomain>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+/// SAML configuration of an [`SsoProvider`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SamlProvider {
+    pub entity_id: String,
+    pub metadata_xml: Option<String>,
+    pub metadata_url: Option<String>,
+    /// Maps SAML assertion attributes to user fields.
+    #[serde(default)]
+    pub attribute_mapping: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SsoDomain {
+    pub domain: String,
+}
+
+/// Attributes of an SSO provider to create or update. Fields left as `None` are not changed on
+/// update.
+///
+/// The SAML metadata must be given either inline as `metadata_xml` or as `metadata_url` when
+/// creating a provider.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SsoProviderAttributes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata_xml: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata_url: Option<String>,
+    /// Replaces the domains mapped to the provider.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domains: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attribute_mapping: Option<Value>,
+}
+
+/// Body of `POST /admin/sso/providers`, only SAML providers are supported by GoTrue.
+#[derive(Debug, Serialize)]
+pub(crate) struct CreateSsoProviderRequest<'a> {
+    #[serde(rename = "type")]
+    pub provider_type: &'static str,
+    #[serde(flatten)]
+    pub attributes: &'a SsoProviderAttributes,
+}
+
+/// Response of `GET /admin/sso/providers`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct SsoProviderList {
+    #[serde(default)]
+    pub items: Vec<SsoProvider>,
+}
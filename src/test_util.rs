@@ -0,0 +1,399 @@
+//! Helpers to test code using this crate without a running GoTrue server.
+//!
+//! [`MockServer`] answers requests on a local port with canned responses, [`user`] and
+//! [`session`] build fixtures for code that takes a [`User`] or [`Session`] directly.
+//!
+//! # Example
+//!
+//! ```
+//! use go_true::{test_util::MockServer, EmailOrPhone};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let server = MockServer::start();
+//!     server.mock_sign_in("email@example.com");
+//!
+//!     let mut client = server.client();
+//!     let session = client
+//!         .sign_in(EmailOrPhone::Email("email@example.com".to_string()), "Abcd1234!")
+//!         .await?;
+//!
+//!     assert_eq!(session.user.email, "email@example.com");
+//!     assert_eq!(server.requests()[0].path, "/token?grant_type=password");
+//!     Ok(())
+//! }
+//! ```
+
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::{api::Api, client::Client, session::Session, user::User};
+
+/// Id of the user returned by [`user`] and [`session`].
+pub const USER_ID: &str = "a5d2b4f0-3b5e-4c36-9b1a-0c2c6a9f8f11";
+
+/// Returns a confirmed user with the given email.
+pub fn user(email: &str) -> User {
+    serde_json::from_value(user_json(email)).expect("User fixture is valid.")
+}
+
+/// Returns a session of [`user`] expiring in an hour.
+///
+/// The access token is an unsigned JWT, so [`decode_jwt`](crate::decode_jwt) works on it but
+/// verifying it fails.
+pub fn session(email: &str) -> Session {
+    serde_json::from_value(session_json(email)).expect("Session fixture is valid.")
+}
+
+fn user_json(email: &str) -> Value {
+    json!({
+        "id": USER_ID,
+        "email": email,
+        "aud": "authenticated",
+        "role": "authenticated",
+        "email_confirmed_at": "2022-08-01T00:00:00Z",
+        "phone": "",
+        "confirmed_at": "2022-08-01T00:00:00Z",
+        "last_sign_in_at": "2022-08-01T00:00:00Z",
+        "app_metadata": { "provider": "email", "providers": ["email"] },
+        "user_metadata": {},
+        "created_at": "2022-08-01T00:00:00Z",
+        "updated_at": "2022-08-01T00:00:00Z"
+    })
+}
+
+fn session_json(email: &str) -> Value {
+    json!({
+        "access_token": access_token(email, 3600),
+        "token_type": "bearer",
+        "expires_in": 3600,
+        "refresh_token": "refresh-token",
+        "user": user_json(email)
+    })
+}
+
+fn access_token(email: &str, expires_in: i64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let header = json!({ "alg": "HS256", "typ": "JWT" });
+    let claims = json!({
+        "sub": USER_ID,
+        "exp": now + expires_in,
+        "iat": now,
+        "aud": "authenticated",
+        "email": email,
+        "role": "authenticated",
+        "aal": "aal1",
+    });
+    let encode = |value: Value| base64::encode_config(value.to_string(), base64::URL_SAFE_NO_PAD);
+
+    format!("{}.{}.signature", encode(header), encode(claims))
+}
+
+/// Canned response of a [`MockServer`].
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl MockResponse {
+    /// Responds with `body` serialized as JSON.
+    pub fn json(status: u16, body: impl Serialize) -> MockResponse {
+        MockResponse {
+            status,
+            headers: vec![(
+                String::from("Content-Type"),
+                String::from("application/json"),
+            )],
+            body: serde_json::to_string(&body).expect("Mock body can be serialized."),
+        }
+    }
+
+    /// Responds with an empty body.
+    pub fn empty(status: u16) -> MockResponse {
+        MockResponse {
+            status,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    /// Responds with an error body as sent by GoTrue, e.g. `400` with `invalid_grant`.
+    pub fn error(status: u16, code: &str, message: &str) -> MockResponse {
+        MockResponse::json(
+            status,
+            json!({ "code": status, "error_code": code, "msg": message }),
+        )
+    }
+
+    /// Responds with `429 Too Many Requests` and a `Retry-After` header.
+    pub fn rate_limited(retry_after_secs: u64) -> MockResponse {
+        MockResponse::error(429, "over_request_rate_limit", "Rate limit exceeded")
+            .header("Retry-After", retry_after_secs.to_string())
+    }
+
+    /// Adds a response header.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> MockResponse {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    fn to_http(&self) -> String {
+        let mut response = format!("HTTP/1.1 {} Mock\r\n", self.status);
+        for (name, value) in &self.headers {
+            response.push_str(&format!("{name}: {value}\r\n"));
+        }
+        response.push_str(&format!(
+            "Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.body.len(),
+            self.body
+        ));
+
+        response
+    }
+}
+
+/// Request received by a [`MockServer`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path including the query string, e.g. `/token?grant_type=password`.
+    pub path: String,
+    /// Headers with lowercase names.
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl RecordedRequest {
+    /// Parses the body as JSON.
+    pub fn json(&self) -> Value {
+        serde_json::from_str(&self.body).unwrap_or(Value::Null)
+    }
+}
+
+struct Route {
+    method: String,
+    path: String,
+    response: MockResponse,
+}
+
+/// GoTrue stand-in listening on a random local port.
+///
+/// Requests are answered with the response mounted for their method and path, ignoring the query
+/// string, or `404` if there is none. The server stops when dropped.
+pub struct MockServer {
+    url: String,
+    routes: Arc<Mutex<Vec<Route>>>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    shutdown: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Starts a server without any mounted responses.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no local port can be bound.
+    pub fn start() -> MockServer {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind mock server.");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let routes: Arc<Mutex<Vec<Route>>> = Arc::default();
+        let requests: Arc<Mutex<Vec<RecordedRequest>>> = Arc::default();
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let routes = routes.clone();
+            let requests = requests.clone();
+            let shutdown = shutdown.clone();
+
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if shutdown.load(Ordering::SeqCst) {
+                        return;
+                    }
+                    if let Ok(stream) = stream {
+                        handle(stream, &routes, &requests);
+                    }
+                }
+            })
+        };
+
+        MockServer {
+            url,
+            routes,
+            requests,
+            shutdown,
+            thread: Some(thread),
+        }
+    }
+
+    /// Url of the server, pass it to [`Api::new`] or [`Client::new`].
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Returns an [`Api`] talking to this server.
+    pub fn api(&self) -> Api {
+        Api::new(self.url())
+    }
+
+    /// Returns a [`Client`] talking to this server.
+    pub fn client(&self) -> Client {
+        Client::new(self.url())
+    }
+
+    /// Answers requests to `method` and `path` with `response`. Mounting a route again replaces
+    /// its response.
+    pub fn mock(&self, method: &str, path: &str, response: MockResponse) -> &Self {
+        let mut routes = self.routes.lock().unwrap();
+        routes.retain(|route| !(route.method == method && route.path == path));
+        routes.push(Route {
+            method: method.to_string(),
+            path: path.to_string(),
+            response,
+        });
+
+        self
+    }
+
+    /// Answers sign ins and refreshes with a [`session`] of `email`.
+    pub fn mock_sign_in(&self, email: &str) -> &Self {
+        self.mock(
+            "POST",
+            "/token",
+            MockResponse::json(200, session_json(email)),
+        )
+    }
+
+    /// Answers sign ups with a [`session`] of `email`, as a server with autoconfirm enabled does.
+    pub fn mock_sign_up(&self, email: &str) -> &Self {
+        self.mock(
+            "POST",
+            "/signup",
+            MockResponse::json(200, session_json(email)),
+        )
+    }
+
+    /// Answers requests for the signed in user with a [`user`] of `email`.
+    pub fn mock_user(&self, email: &str) -> &Self {
+        self.mock("GET", "/user", MockResponse::json(200, user_json(email)))
+    }
+
+    /// Accepts OTPs, magic links and password recoveries.
+    pub fn mock_otp(&self) -> &Self {
+        self.mock("POST", "/otp", MockResponse::json(200, json!({})))
+            .mock("POST", "/recover", MockResponse::json(200, json!({})))
+    }
+
+    /// Accepts sign outs.
+    pub fn mock_sign_out(&self) -> &Self {
+        self.mock("POST", "/logout", MockResponse::empty(204))
+    }
+
+    /// Returns the requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+
+        // Wake up the blocking accept so the thread sees the shutdown flag.
+        let _ = TcpStream::connect(self.url.trim_start_matches("http://"));
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn handle(
+    mut stream: TcpStream,
+    routes: &Mutex<Vec<Route>>,
+    requests: &Mutex<Vec<RecordedRequest>>,
+) {
+    let request = match read_request(&mut stream) {
+        Some(request) => request,
+        None => return,
+    };
+
+    let path = request.path.split('?').next().unwrap_or_default();
+    let response = routes
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|route| route.method == request.method && route.path == path)
+        .map(|route| route.response.clone())
+        .unwrap_or_else(|| MockResponse::error(404, "not_found", "No mock mounted for this route"));
+
+    requests.lock().unwrap().push(request);
+    let _ = stream.write_all(response.to_http().as_bytes());
+}
+
+fn read_request(stream: &mut TcpStream) -> Option<RecordedRequest> {
+    let mut raw = Vec::new();
+    let mut buffer = [0; 4096];
+
+    let (head, body_start) = loop {
+        let read = stream.read(&mut buffer).ok()?;
+        if read == 0 {
+            return None;
+        }
+        raw.extend_from_slice(&buffer[..read]);
+
+        if let Some(position) = raw.windows(4).position(|window| window == b"\r\n\r\n") {
+            break (
+                String::from_utf8_lossy(&raw[..position]).to_string(),
+                position + 4,
+            );
+        }
+    };
+
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let headers: HashMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let content_length = headers
+        .get("content-length")
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    while raw.len() < body_start + content_length {
+        let read = stream.read(&mut buffer).ok()?;
+        if read == 0 {
+            break;
+        }
+        raw.extend_from_slice(&buffer[..read]);
+    }
+    let body = String::from_utf8_lossy(&raw[body_start..]).to_string();
+
+    Some(RecordedRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
@@ -0,0 +1,29 @@
+/// Point in time as sent by GoTrue, e.g. `2022-08-01T12:30:00.000000Z`.
+///
+/// Deserialized into a [`chrono::DateTime<Utc>`](chrono::DateTime) with the `chrono` feature.
+#[cfg(feature = "chrono")]
+pub type Timestamp = chrono::DateTime<chrono::Utc>;
+
+/// Point in time as sent by GoTrue, e.g. `2022-08-01T12:30:00.000000Z`.
+///
+/// Enable the `chrono` feature to get a `chrono::DateTime<Utc>` instead of the raw string.
+#[cfg(not(feature = "chrono"))]
+pub type Timestamp = String;
+
+/// Deserializes an optional timestamp, treating the empty string GoTrue sends for unset values
+/// like `email_change_sent_at` as `None`.
+pub(crate) fn deserialize_optional<'de, D>(deserializer: D) -> Result<Option<Timestamp>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::Deserialize;
+
+    let value = Option::<String>::deserialize(deserializer)?.filter(|value| !value.is_empty());
+
+    #[cfg(feature = "chrono")]
+    let value = value
+        .map(|value| value.parse().map_err(serde::de::Error::custom))
+        .transpose()?;
+
+    Ok(value)
+}
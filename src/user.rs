@@ -1,14 +1,51 @@
-use serde::Deserialize;
+use std::collections::HashMap;
 
-#[derive(Debug, Clone, Deserialize)]
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{identity::Identity, mfa::Factor, timestamp::Timestamp};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct User {
     pub id: String,
+    #[cfg_attr(feature = "lenient", serde(default))]
     pub email: String,
+    #[cfg_attr(feature = "lenient", serde(default))]
     pub aud: String,
+    #[cfg_attr(feature = "lenient", serde(default))]
     pub role: String,
-    pub email_confirmed_at: Option<String>,
+    pub email_confirmed_at: Option<Timestamp>,
+    #[cfg_attr(feature = "lenient", serde(default))]
     pub phone: String,
-    pub last_sign_in_at: Option<String>,
-    pub created_at: String,
-    pub updated_at: String,
+    pub phone_confirmed_at: Option<Timestamp>,
+    /// Time the email or phone was confirmed, whichever happened first.
+    pub confirmed_at: Option<Timestamp>,
+    pub confirmation_sent_at: Option<Timestamp>,
+    pub recovery_sent_at: Option<Timestamp>,
+    pub invited_at: Option<Timestamp>,
+    pub last_sign_in_at: Option<Timestamp>,
+    pub banned_until: Option<Timestamp>,
+    /// Metadata only writable with the service role, e.g. the sign in provider.
+    #[serde(default)]
+    pub app_metadata: Value,
+    /// Metadata writable by the user, see [`UserAttributes::data`](crate::UserAttributes::data).
+    #[serde(default)]
+    pub user_metadata: Value,
+    pub identities: Option<Vec<Identity>>,
+    pub factors: Option<Vec<Factor>>,
+    #[serde(default)]
+    pub is_anonymous: bool,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+    /// Fields sent by GoTrue that are not modelled by this struct yet.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// Metadata as contained in the user returned by `/user`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct UserMetadata {
+    #[serde(default)]
+    pub user_metadata: Value,
 }
@@ -1,8 +1,35 @@
+use serde::Serialize;
 use serde_json::Value;
 
-#[derive(Debug)]
+/// Attributes changed by [`Api::update_user`](crate::Api::update_user).
+///
+/// Only the fields that are set are sent, so a single attribute can be updated on its own.
+///
+/// # Example
+///
+/// ```
+/// use go_true::UserAttributes;
+/// use serde_json::json;
+///
+/// let attributes = UserAttributes {
+///     data: Some(json!({ "theme": "dark" })),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct UserAttributes {
-    pub email: String,
-    pub password: String,
-    pub data: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// New phone number, confirmed with the OTP sent to it, see
+    /// [`Api::update_phone`](crate::Api::update_phone).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    /// Nonce sent by [`Api::reauthenticate`](crate::Api::reauthenticate), required to change the
+    /// password when the server enforces secure password changes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
 }
@@ -1,7 +1,27 @@
 use crate::user::User;
 use serde::Deserialize;
 
+/// Users as returned by `/admin/users`.
 #[derive(Debug, Clone, Deserialize)]
+#[serde(from = "UserListShape")]
+#[non_exhaustive]
 pub struct UserList {
     pub users: Vec<User>,
 }
+
+/// Depending on the version GoTrue answers with a bare array or wraps it as
+/// `{"users": [...], "aud": "..."}`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum UserListShape {
+    Array(Vec<User>),
+    Object { users: Vec<User> },
+}
+
+impl From<UserListShape> for UserList {
+    fn from(shape: UserListShape) -> Self {
+        match shape {
+            UserListShape::Array(users) | UserListShape::Object { users } => UserList { users },
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+use crate::timestamp::Timestamp;
+
+/// A refresh token session of a user, as listed by
+/// [`AdminApi::list_user_sessions`](crate::AdminApi::list_user_sessions).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct UserSession {
+    pub id: String,
+    pub user_id: String,
+    /// Authenticator assurance level, `aal1` or `aal2` after a MFA challenge.
+    pub aal: Option<String>,
+    /// MFA factor the session was verified with.
+    pub factor_id: Option<String>,
+    /// Point in time after which the session can't be refreshed anymore.
+    pub not_after: Option<Timestamp>,
+    pub refreshed_at: Option<Timestamp>,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
@@ -1,11 +1,26 @@
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize)]
+use crate::timestamp::Timestamp;
+
+#[derive(Debug, Clone, Deserialize)]
+#[non_exhaustive]
 pub struct UserUpdate {
     pub id: String,
     pub email: String,
+    /// Empty unless an email change is pending.
+    #[serde(default)]
     pub new_email: String,
-    pub email_change_sent_at: String,
-    pub created_at: String,
-    pub updated_at: String,
+    /// `None` unless an email change is pending.
+    #[serde(default, deserialize_with = "crate::timestamp::deserialize_optional")]
+    pub email_change_sent_at: Option<Timestamp>,
+    #[serde(default)]
+    pub phone: String,
+    /// Empty unless a phone change is pending.
+    #[serde(default)]
+    pub new_phone: String,
+    /// `None` unless a phone change is pending.
+    #[serde(default, deserialize_with = "crate::timestamp::deserialize_optional")]
+    pub phone_change_sent_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
 }
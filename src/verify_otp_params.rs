@@ -0,0 +1,53 @@
+use serde::Serialize;
+
+/// Kind of token being verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OtpType {
+    Signup,
+    Invite,
+    Magiclink,
+    Recovery,
+    Email,
+    Sms,
+    PhoneChange,
+    EmailChange,
+}
+
+/// Parameters for [`Api::verify_otp_with`](crate::Api::verify_otp_with).
+///
+/// # Example
+///
+/// ```
+/// use go_true::{OtpType, VerifyOtpParams};
+///
+/// let params = VerifyOtpParams::Email {
+///     email: "email@example.com".to_string(),
+///     token: "123456".to_string(),
+///     otp_type: OtpType::Magiclink,
+/// };
+/// ```
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum VerifyOtpParams {
+    /// Token sent by email.
+    Email {
+        email: String,
+        token: String,
+        #[serde(rename = "type")]
+        otp_type: OtpType,
+    },
+    /// Token sent by SMS.
+    Phone {
+        phone: String,
+        token: String,
+        #[serde(rename = "type")]
+        otp_type: OtpType,
+    },
+    /// Hashed token taken from an email link.
+    TokenHash {
+        token_hash: String,
+        #[serde(rename = "type")]
+        otp_type: OtpType,
+    },
+}
@@ -0,0 +1,52 @@
+use go_true::{error::Error, parse_auth_callback};
+
+#[test]
+fn it_should_parse_tokens_from_fragment() -> Result<(), Error> {
+    let callback = parse_auth_callback(
+        "https://example.com/welcome?next=%2Fhome#access_token=access-token&expires_at=1700003600\
+         &expires_in=3600&provider_token=github-token&refresh_token=refresh-token\
+         &token_type=bearer&type=magiclink",
+    )?;
+
+    assert_eq!(callback.access_token, "access-token");
+    assert_eq!(callback.refresh_token, "refresh-token");
+    assert_eq!(callback.token_type, "bearer");
+    assert_eq!(callback.expires_in, 3600);
+    assert_eq!(callback.expires_at, Some(1700003600));
+    assert_eq!(callback.callback_type.as_deref(), Some("magiclink"));
+    assert_eq!(callback.provider_token.as_deref(), Some("github-token"));
+    assert_eq!(callback.provider_refresh_token, None);
+
+    Ok(())
+}
+
+#[test]
+fn it_should_return_error_reported_in_callback() {
+    let result = parse_auth_callback(
+        "https://example.com/welcome#error=access_denied&error_code=otp_expired\
+         &error_description=Email+link+is+invalid+or+has+expired",
+    );
+
+    match result {
+        Err(Error::AuthCallbackFailed { code, message }) => {
+            assert_eq!(code, "otp_expired");
+            assert_eq!(message, "Email link is invalid or has expired");
+        }
+        other => panic!("expected callback error, got {other:?}"),
+    }
+}
+
+#[test]
+fn it_should_reject_callback_without_tokens() {
+    for url in [
+        "not a url",
+        "https://example.com/welcome",
+        "https://example.com/welcome?code=auth-code",
+        "https://example.com/welcome#access_token=access-token&refresh_token=refresh-token",
+    ] {
+        assert!(
+            matches!(parse_auth_callback(url), Err(Error::InvalidAuthCallback(_))),
+            "{url}"
+        );
+    }
+}
@@ -0,0 +1,55 @@
+#![cfg(all(feature = "blocking", feature = "test-util"))]
+
+use go_true::{
+    blocking::Api,
+    error::ApiError,
+    test_util::{self, MockResponse, MockServer},
+    EmailOrPhone,
+};
+use std::error::Error;
+
+#[test]
+fn it_should_sign_in_without_runtime() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start();
+    server.mock_sign_in("email@example.com");
+
+    let api = Api::new(server.url());
+    let session = api.sign_in(
+        EmailOrPhone::Email(String::from("email@example.com")),
+        &String::from("Abcd1234!"),
+    )?;
+
+    assert_eq!(session.user.id, test_util::USER_ID);
+    assert_eq!(session.refresh_token, "refresh-token");
+
+    let request = &server.requests()[0];
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/token?grant_type=password");
+    assert_eq!(request.headers["accept"], "application/json");
+
+    Ok(())
+}
+
+#[test]
+fn it_should_return_gotrue_error() {
+    let server = MockServer::start();
+    server.mock(
+        "POST",
+        "/token",
+        MockResponse::error(400, "invalid_grant", "Invalid login credentials"),
+    );
+
+    let api = Api::new(server.url());
+    let result = api.sign_in(
+        EmailOrPhone::Email(String::from("email@example.com")),
+        &String::from("wrong"),
+    );
+
+    match result {
+        Err(ApiError::GoTrue { status, code, .. }) => {
+            assert_eq!(status, 400);
+            assert_eq!(code, "invalid_grant");
+        }
+        other => panic!("expected GoTrue error, got {other:?}"),
+    }
+}
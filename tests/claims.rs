@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+
+use go_true::{decode_jwt, error::Error, Session};
+use hmac::{Hmac, Mac};
+use jwt::SignWithKey;
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+fn get_token() -> String {
+    let key: Hmac<Sha256> = Hmac::new_from_slice(b"secret").unwrap();
+    let mut claims: BTreeMap<&str, Value> = BTreeMap::new();
+    claims.insert("sub", json!("a5d2b4f0-3b5e-4c36-9b1a-0c2c6a9f8f11"));
+    claims.insert("exp", json!(1700000000));
+    claims.insert("email", json!("email@example.com"));
+    claims.insert("role", json!("authenticated"));
+    claims.insert("aal", json!("aal1"));
+    claims.insert("app_metadata", json!({ "provider": "email" }));
+
+    claims.sign_with_key(&key).unwrap()
+}
+
+#[test]
+fn it_should_decode_claims() -> Result<(), Error> {
+    let claims = decode_jwt(&get_token())?;
+
+    assert_eq!(claims.sub, "a5d2b4f0-3b5e-4c36-9b1a-0c2c6a9f8f11");
+    assert_eq!(claims.exp, 1700000000);
+    assert_eq!(claims.email.as_deref(), Some("email@example.com"));
+    assert_eq!(claims.role.as_deref(), Some("authenticated"));
+    assert_eq!(claims.aal.as_deref(), Some("aal1"));
+    assert_eq!(claims.app_metadata["provider"], "email");
+
+    Ok(())
+}
+
+#[test]
+fn it_should_decode_claims_of_session() -> Result<(), Error> {
+    let session: Session = serde_json::from_value(json!({
+        "access_token": get_token(),
+        "token_type": "bearer",
+        "expires_in": 3600,
+        "refresh_token": "refresh-token",
+        "user": {
+            "id": "a5d2b4f0-3b5e-4c36-9b1a-0c2c6a9f8f11",
+            "email": "email@example.com",
+            "aud": "authenticated",
+            "role": "authenticated",
+            "email_confirmed_at": null,
+            "phone": "",
+            "last_sign_in_at": null,
+            "created_at": "2022-08-01T00:00:00Z",
+            "updated_at": "2022-08-01T00:00:00Z"
+        }
+    }))
+    .unwrap();
+
+    let claims = session.decode_claims()?;
+
+    assert_eq!(claims.sub, session.user.id);
+
+    Ok(())
+}
+
+#[test]
+fn it_should_reject_malformed_token() {
+    let result = decode_jwt("not-a-jwt");
+
+    assert!(matches!(result, Err(Error::InvalidJwt(_))));
+}
+
+#[cfg(feature = "verify")]
+mod verify {
+    use super::*;
+    use go_true::Api;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn sign(secret: &[u8], exp: u64) -> String {
+        let key: Hmac<Sha256> = Hmac::new_from_slice(secret).unwrap();
+        let mut claims: BTreeMap<&str, Value> = BTreeMap::new();
+        claims.insert("sub", json!("a5d2b4f0-3b5e-4c36-9b1a-0c2c6a9f8f11"));
+        claims.insert("aud", json!("authenticated"));
+        claims.insert("exp", json!(exp));
+
+        claims.sign_with_key(&key).unwrap()
+    }
+
+    fn in_one_hour() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 3600
+    }
+
+    #[test]
+    fn it_should_verify_token() -> Result<(), Error> {
+        let claims = Api::verify_jwt(&sign(b"secret", in_one_hour()), "secret")?;
+
+        assert_eq!(claims.sub, "a5d2b4f0-3b5e-4c36-9b1a-0c2c6a9f8f11");
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_should_reject_token_with_wrong_secret() {
+        let result = Api::verify_jwt(&sign(b"other", in_one_hour()), "secret");
+
+        assert!(matches!(result, Err(Error::InvalidJwt(_))));
+    }
+
+    #[test]
+    fn it_should_reject_expired_token() {
+        let result = Api::verify_jwt(&sign(b"secret", 1000), "secret");
+
+        assert!(matches!(result, Err(Error::InvalidJwt(_))));
+    }
+}
@@ -1,4 +1,4 @@
-use go_true::{Client, EmailOrPhone, UserAttributes};
+use go_true::{AuthChangeEvent, Client, EmailOrPhone, UserAttributes};
 use rand::{distributions::Alphanumeric, Rng};
 use serde_json::json;
 use std::error::Error;
@@ -28,7 +28,7 @@ async fn it_signs_up_with_email() -> Result<(), Box<dyn Error>> {
         .sign_up(EmailOrPhone::Email(email.clone()), &password)
         .await?;
 
-    assert_eq!(email, res.user.email);
+    assert_eq!(email, res.user().email);
 
     Ok(())
 }
@@ -70,6 +70,32 @@ async fn it_signs_in_with_email() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[tokio::test]
+async fn it_should_expose_current_session() -> Result<(), Box<dyn Error>> {
+    let email = get_random_email();
+    let password = String::from("Abcd1234!");
+
+    let mut client = get_client();
+    assert!(!client.is_authenticated());
+
+    let session = client
+        .sign_up(EmailOrPhone::Email(email), &password)
+        .await?
+        .into_session()
+        .expect("autoconfirm is enabled");
+
+    assert!(client.is_authenticated());
+    assert_eq!(client.access_token(), Some(session.access_token));
+    assert_eq!(client.current_session().unwrap().user.id, session.user.id);
+
+    client.sign_out().await?;
+
+    assert!(!client.is_authenticated());
+    assert_eq!(client.access_token(), None);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn it_should_return_error_when_credentials_are_wrong_on_signin() -> Result<(), Box<dyn Error>>
 {
@@ -94,6 +120,40 @@ async fn it_should_return_error_when_credentials_are_wrong_on_signin() -> Result
     Ok(())
 }
 
+#[tokio::test]
+async fn it_should_return_api_error_when_server_is_unreachable() -> Result<(), Box<dyn Error>> {
+    let email = get_random_email();
+    let password = String::from("Abcd1234!");
+
+    let mut client = Client::new("http://localhost:1".to_string());
+    let result = client.sign_in(EmailOrPhone::Email(email), &password).await;
+
+    match result {
+        Ok(_) => panic!("Should throw error"),
+        Err(e) => assert!(matches!(
+            e,
+            go_true::error::Error::Api(go_true::error::ApiError::Transport(_))
+        )),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_should_expose_source_of_api_errors() {
+    let password = String::from("Abcd1234!");
+
+    let mut client = Client::new("http://localhost:1".to_string());
+    let result = client
+        .sign_in(EmailOrPhone::Email(get_random_email()), &password)
+        .await;
+
+    let error: Box<dyn Error> = Box::new(result.unwrap_err());
+    let source = error.source().expect("API errors have a source");
+
+    assert!(source.source().unwrap().is::<reqwest::Error>());
+}
+
 #[tokio::test]
 async fn it_should_return_error_if_no_session_when_refreshing() -> Result<(), Box<dyn Error>> {
     let mut client = get_client();
@@ -157,6 +217,27 @@ async fn it_does_not_send_magic_link_with_invalid_email() -> Result<(), Box<dyn
     Ok(())
 }
 
+#[tokio::test]
+async fn it_should_return_error_when_otp_is_wrong() -> Result<(), Box<dyn Error>> {
+    let email = get_random_email();
+    let password = String::from("Abcd1234!");
+
+    let mut client = get_client();
+    client
+        .sign_up(EmailOrPhone::Email(email.clone()), &password)
+        .await?;
+
+    let params = json!({ "email": email, "token": "000000", "type": "magiclink" });
+    let result = client.verify_otp(params).await;
+
+    match result {
+        Ok(_) => panic!("Should throw error"),
+        Err(_) => assert!(!client.is_authenticated()),
+    }
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn it_should_log_out() -> Result<(), Box<dyn Error>> {
     let email = get_random_email();
@@ -219,9 +300,11 @@ async fn it_should_update_user() -> Result<(), Box<dyn Error>> {
 
     let new_email = get_random_email();
     let attributes = UserAttributes {
-        email: new_email.clone(),
-        password: "Abcd12345!".to_string(),
-        data: json!({ "test": "test" }),
+        email: Some(new_email.clone()),
+        password: Some("Abcd12345!".to_string()),
+        data: Some(json!({ "test": "test" })),
+        phone: None,
+        nonce: None,
     };
 
     let update = client.update_user(attributes).await?;
@@ -232,16 +315,102 @@ async fn it_should_update_user() -> Result<(), Box<dyn Error>> {
 }
 
 #[tokio::test]
-async fn it_should_set_session_by_refresh_token() -> Result<(), Box<dyn Error>> {
+async fn it_should_get_signed_in_user() -> Result<(), Box<dyn Error>> {
     let email = get_random_email();
     let password = String::from("Abcd1234!");
 
     let mut client = get_client();
-    let old_session = client
+    client
         .sign_up(EmailOrPhone::Email(email.clone()), &password)
         .await?;
 
-    let session = client.set_session(&old_session.refresh_token).await?;
+    let user = client.get_user().await?;
+
+    assert_eq!(user.email, email);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_should_return_error_if_no_session_when_getting_user() {
+    let client = get_client();
+    let result = client.get_user().await;
+
+    assert!(matches!(
+        result,
+        Err(go_true::error::Error::NotAuthenticated)
+    ));
+}
+
+#[tokio::test]
+async fn it_should_emit_auth_state_changes() -> Result<(), Box<dyn Error>> {
+    let email = get_random_email();
+    let password = String::from("Abcd1234!");
+
+    let mut client = get_client();
+    let mut events = client.on_auth_state_change();
+
+    client
+        .sign_up(EmailOrPhone::Email(email.clone()), &password)
+        .await?;
+    client.refresh_session().await?;
+    client.sign_out().await?;
+
+    assert!(matches!(events.recv().await?, AuthChangeEvent::SignedIn(_)));
+    assert!(matches!(
+        events.recv().await?,
+        AuthChangeEvent::TokenRefreshed(_)
+    ));
+    assert!(matches!(events.recv().await?, AuthChangeEvent::SignedOut));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_should_update_password() -> Result<(), Box<dyn Error>> {
+    let email = get_random_email();
+    let password = String::from("Abcd1234!");
+    let new_password = String::from("Abcd12345!");
+
+    let mut client = get_client();
+    client
+        .sign_up(EmailOrPhone::Email(email.clone()), &password)
+        .await?;
+    client.update_password(&new_password).await?;
+
+    let session = client
+        .sign_in(EmailOrPhone::Email(email), &new_password)
+        .await?;
+
+    assert!(!session.access_token.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_should_return_error_if_token_is_malformed_when_setting_session() {
+    let mut client = get_client();
+    let result = client.set_session("not-a-jwt", "refresh-token").await;
+
+    assert!(matches!(result, Err(go_true::error::Error::InvalidJwt(_))));
+    assert!(!client.is_authenticated());
+}
+
+#[tokio::test]
+async fn it_should_set_session_from_tokens() -> Result<(), Box<dyn Error>> {
+    let email = get_random_email();
+    let password = String::from("Abcd1234!");
+
+    let mut client = get_client();
+    let old_session = client
+        .sign_up(EmailOrPhone::Email(email.clone()), &password)
+        .await?
+        .into_session()
+        .expect("autoconfirm is enabled");
+
+    let session = client
+        .set_session(&old_session.access_token, &old_session.refresh_token)
+        .await?;
     assert_eq!(old_session.user.email, session.user.email);
 
     Ok(())
@@ -0,0 +1,32 @@
+use go_true::{error::Error, AdminApi, Api};
+
+// Environment variables are process wide, so every case runs in this single test.
+#[test]
+fn it_should_read_configuration_from_env() {
+    std::env::remove_var("GOTRUE_URL");
+    std::env::remove_var("GOTRUE_API_KEY");
+    std::env::remove_var("GOTRUE_SERVICE_ROLE_KEY");
+
+    let result = Api::from_env();
+    assert!(matches!(result, Err(Error::InvalidConfig(reason)) if reason.contains("GOTRUE_URL")));
+
+    std::env::set_var("GOTRUE_URL", "not a url");
+    assert!(matches!(Api::from_env(), Err(Error::InvalidConfig(_))));
+
+    std::env::set_var("GOTRUE_URL", "http://localhost:9998/");
+    let api = Api::from_env().unwrap();
+    assert_eq!(api.url(), "http://localhost:9998");
+    assert!(api.headers().get("apikey").is_none());
+
+    std::env::set_var("GOTRUE_API_KEY", "anon-key");
+    let api = Api::from_env().unwrap();
+    assert_eq!(api.headers()["apikey"], "anon-key");
+
+    let result = AdminApi::from_env();
+    assert!(
+        matches!(result, Err(Error::InvalidConfig(reason)) if reason.contains("GOTRUE_SERVICE_ROLE_KEY"))
+    );
+
+    std::env::set_var("GOTRUE_SERVICE_ROLE_KEY", "service-role-key");
+    assert!(AdminApi::from_env().is_ok());
+}
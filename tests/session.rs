@@ -0,0 +1,74 @@
+use go_true::Session;
+use serde_json::json;
+use std::time::{Duration, SystemTime};
+
+fn get_session(expires_in: i32) -> Session {
+    let session = json!({
+        "access_token": "access-token",
+        "token_type": "bearer",
+        "expires_in": expires_in,
+        "refresh_token": "refresh-token",
+        "user": {
+            "id": "a5d2b4f0-3b5e-4c36-9b1a-0c2c6a9f8f11",
+            "email": "email@example.com",
+            "aud": "authenticated",
+            "role": "authenticated",
+            "email_confirmed_at": null,
+            "phone": "",
+            "last_sign_in_at": null,
+            "created_at": "2022-08-01T00:00:00Z",
+            "updated_at": "2022-08-01T00:00:00Z"
+        }
+    });
+
+    serde_json::from_value(session).unwrap()
+}
+
+#[test]
+fn it_should_not_be_expired_after_creation() {
+    let session = get_session(3600);
+
+    assert!(!session.is_expired());
+    assert!(session.time_until_expiry().unwrap() > Duration::from_secs(3500));
+    assert!(session.expires_at().unwrap() > SystemTime::now());
+}
+
+#[test]
+fn it_should_be_expired_within_leeway() {
+    let session = get_session(5);
+
+    assert!(session.is_expired());
+    assert!(!session.is_expired_with_leeway(Duration::ZERO));
+}
+
+#[test]
+fn it_should_be_expired_after_expires_in() {
+    let mut session = get_session(60);
+    session.created_at = SystemTime::now() - Duration::from_secs(120);
+
+    assert!(session.is_expired());
+    assert_eq!(session.time_until_expiry(), None);
+}
+
+#[cfg(feature = "lenient")]
+#[test]
+fn it_should_tolerate_other_server_versions() {
+    let session: Session = serde_json::from_value(json!({
+        "access_token": "access-token",
+        "expires_in": "3600",
+        "refresh_token": "refresh-token",
+        "user": {
+            "id": "a5d2b4f0-3b5e-4c36-9b1a-0c2c6a9f8f11",
+            "email_confirmed_at": null,
+            "last_sign_in_at": null,
+            "created_at": "2022-08-01T00:00:00Z",
+            "updated_at": "2022-08-01T00:00:00Z"
+        }
+    }))
+    .unwrap();
+
+    assert_eq!(session.expires_in, 3600);
+    assert_eq!(session.token_type, "");
+    assert_eq!(session.user.email, "");
+    assert_eq!(session.user.aud, "");
+}
@@ -0,0 +1,59 @@
+use go_true::{FileSessionStore, Session, SessionStore};
+use rand::{distributions::Alphanumeric, Rng};
+use serde_json::json;
+
+fn get_session() -> Session {
+    let session = json!({
+        "access_token": "access-token",
+        "token_type": "bearer",
+        "expires_in": 3600,
+        "refresh_token": "refresh-token",
+        "user": {
+            "id": "a5d2b4f0-3b5e-4c36-9b1a-0c2c6a9f8f11",
+            "email": "email@example.com",
+            "aud": "authenticated",
+            "role": "authenticated",
+            "email_confirmed_at": null,
+            "phone": "",
+            "last_sign_in_at": null,
+            "created_at": "2022-08-01T00:00:00Z",
+            "updated_at": "2022-08-01T00:00:00Z"
+        }
+    });
+
+    serde_json::from_value(session).unwrap()
+}
+
+fn get_file_store() -> FileSessionStore {
+    let file_name: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(7)
+        .map(char::from)
+        .collect();
+
+    FileSessionStore::new(std::env::temp_dir().join(format!("{file_name}.json")))
+}
+
+#[tokio::test]
+async fn it_should_save_and_load_session_from_file() {
+    let store = get_file_store();
+    let session = get_session();
+
+    store.save(&session).await;
+    let loaded = store.load().await.unwrap();
+
+    assert_eq!(loaded.access_token, session.access_token);
+    assert_eq!(loaded.created_at, session.created_at);
+
+    store.clear().await;
+}
+
+#[tokio::test]
+async fn it_should_clear_session_from_file() {
+    let store = get_file_store();
+
+    store.save(&get_session()).await;
+    store.clear().await;
+
+    assert!(store.load().await.is_none());
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "test-util")]
+
+use go_true::{
+    error::ApiError,
+    test_util::{self, MockResponse, MockServer},
+    EmailOrPhone,
+};
+use std::error::Error;
+
+#[tokio::test]
+async fn it_should_answer_with_mounted_responses() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start();
+    server.mock_sign_in("email@example.com").mock_sign_out();
+
+    let mut client = server.client();
+    let session = client
+        .sign_in(
+            EmailOrPhone::Email(String::from("email@example.com")),
+            "Abcd1234!",
+        )
+        .await?;
+    client.sign_out().await?;
+
+    assert_eq!(session.decode_claims()?.sub, test_util::USER_ID);
+
+    let requests = server.requests();
+    assert_eq!(requests[0].path, "/token?grant_type=password");
+    assert_eq!(requests[0].json()["password"], "Abcd1234!");
+    assert_eq!(requests[1].method, "POST");
+    assert_eq!(requests[1].path, "/logout");
+    assert_eq!(
+        requests[1].headers["authorization"],
+        format!("Bearer {}", session.access_token)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn it_should_answer_unmounted_routes_with_not_found() {
+    let server = MockServer::start();
+    server.mock("GET", "/settings", MockResponse::rate_limited(30));
+
+    let api = server.api();
+    let error = api.get_user("access-token").await.unwrap_err();
+    assert_eq!(error.status(), Some(404));
+
+    let error = api.get_settings().await.unwrap_err();
+    assert!(matches!(error, ApiError::RateLimited { .. }));
+}
+
+#[test]
+fn it_should_build_fixtures() {
+    let session = test_util::session("email@example.com");
+
+    assert_eq!(session.user.email, "email@example.com");
+    assert!(!session.is_expired());
+    assert_eq!(test_util::user("email@example.com").id, test_util::USER_ID);
+}
@@ -0,0 +1,38 @@
+//! Run in a headless browser with `wasm-pack test --headless --firefox -- --test wasm`.
+#![cfg(target_arch = "wasm32")]
+
+use go_true::{Api, EmailOrPhone};
+use rand::{distributions::Alphanumeric, Rng};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn get_random_email() -> String {
+    let random_string: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(7)
+        .map(|c| c.to_ascii_lowercase())
+        .map(char::from)
+        .collect();
+
+    format!("{random_string}@example.com")
+}
+
+#[wasm_bindgen_test]
+async fn it_signs_in_from_the_browser() {
+    let email = get_random_email();
+    let password = String::from("Abcd1234!");
+
+    let api = Api::new(String::from("http://localhost:9998"));
+    api.sign_up(EmailOrPhone::Email(email.clone()), &password)
+        .await
+        .unwrap();
+
+    let session = api
+        .sign_in(EmailOrPhone::Email(email.clone()), &password)
+        .await
+        .unwrap();
+
+    assert_eq!(session.user.email, email);
+    assert!(!session.is_expired());
+}